@@ -98,6 +98,10 @@ fn main() -> Result<()> {
             .file("./cxx/control.cpp")
             .file("./cxx/render.cpp")
             .file("./cxx/display.cpp")
+            .file("./cxx/request.cpp")
+            .file("./cxx/download.cpp")
+            .file("./cxx/devtools.cpp")
+            .file("./cxx/print.cpp")
             .file("./cxx/webview.cpp")
             .file("./cxx/scheme_handler.cpp");
 
@@ -143,7 +147,6 @@ fn main() -> Result<()> {
         {
             println!("cargo:rustc-link-lib=cef");
             println!("cargo:rustc-link-lib=cef_dll_wrapper");
-            println!("cargo:rustc-link-lib=X11");
         }
 
         #[cfg(target_os = "macos")]
@@ -7,7 +7,10 @@
 
 #include "app.h"
 
+#include "include/base/cef_callback.h"
+#include "include/wrapper/cef_closure_task.h"
 #include "include/wrapper/cef_helpers.h"
+#include "request.h"
 #include "scheme_handler.h"
 
 IApp::IApp(const WebviewOptions* settings, CreateWebviewCallback callback, void* ctx)
@@ -18,7 +21,7 @@ IApp::IApp(const WebviewOptions* settings, CreateWebviewCallback callback, void*
     cef_settings.windowless_rendering_enabled = true;
     cef_settings.chrome_runtime = false;
     cef_settings.no_sandbox = true;
-    cef_settings.background_color = 0x00ffffff;
+    cef_settings.background_color = settings->background_color;
 
     // macos not support the multi threaded message loop.
 #ifdef MACOS
@@ -44,6 +47,11 @@ IApp::IApp(const WebviewOptions* settings, CreateWebviewCallback callback, void*
     {
         _scheme_path = std::string(settings->scheme_path);
     }
+
+    if (settings->proxy != nullptr)
+    {
+        _proxy = ProxyToValue(settings->proxy);
+    }
 }
 
 CefRefPtr<CefBrowserProcessHandler> IApp::GetBrowserProcessHandler()
@@ -60,9 +68,44 @@ void IApp::OnContextInitialized()
         RegisterSchemeHandlerFactory(_scheme_path.value());
     }
 
+    if (_proxy != nullptr)
+    {
+        SetProxyPreference(CefRequestContext::GetGlobalContext(), _proxy);
+    }
+
+    _is_initialized = true;
     _callback(_ctx);
 }
 
+void IApp::SetProxy(const ProxyOptions* proxy)
+{
+    assert(proxy);
+
+    // The options are owned by the caller, convert them before leaving its
+    // thread.
+    SetProxyValue(ProxyToValue(proxy));
+}
+
+void IApp::SetProxyValue(CefRefPtr<CefValue> proxy)
+{
+    // _proxy and _is_initialized are shared with OnContextInitialized, only
+    // touch them on the UI thread.
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI, base::BindOnce(&IApp::SetProxyValue, CefRefPtr<IApp>(this), proxy));
+        return;
+    }
+
+    _proxy = proxy;
+
+    // Before the context is initialized, the proxy is applied in
+    // OnContextInitialized.
+    if (_is_initialized)
+    {
+        SetProxyPreference(CefRequestContext::GetGlobalContext(), _proxy);
+    }
+}
+
 CefRefPtr<CefClient> IApp::GetDefaultClient()
 {
     return nullptr;
@@ -81,9 +124,19 @@ CefRefPtr<IBrowser> IApp::CreateBrowser(std::string url,
     CefBrowserSettings broswer_settings;
     broswer_settings.windowless_frame_rate = settings.frame_rate;
     broswer_settings.webgl = cef_state_t::STATE_DISABLED;
-    broswer_settings.background_color = 0x00ffffff;
+    broswer_settings.background_color = settings.background_color;
     broswer_settings.databases = cef_state_t::STATE_DISABLED;
 
+    // A page with its own proxy gets its own in-memory request context, so the
+    // proxy does not leak into the other pages.
+    CefRefPtr<CefRequestContext> request_context = nullptr;
+    if (settings.proxy != nullptr)
+    {
+        request_context = CefRequestContext::CreateContext(CefRequestContextSettings(),
+                                                           new IRequestContext(ProxyToValue(settings.proxy)));
+        settings.proxy = nullptr;
+    }
+
     CefWindowInfo window_info;
 
     if (settings.window_handle)
@@ -91,6 +144,7 @@ CefRefPtr<IBrowser> IApp::CreateBrowser(std::string url,
         if (settings.is_offscreen)
         {
             window_info.SetAsWindowless((CefWindowHandle)(settings.window_handle));
+            window_info.external_begin_frame_enabled = settings.external_begin_frame;
         }
         else
         {
@@ -100,7 +154,7 @@ CefRefPtr<IBrowser> IApp::CreateBrowser(std::string url,
     }
 
     CefRefPtr<IBrowser> browser = new IBrowser(settings, observer, ctx);
-    CefBrowserHost::CreateBrowser(window_info, browser, url, broswer_settings, nullptr, nullptr);
+    CefBrowserHost::CreateBrowser(window_info, browser, url, broswer_settings, nullptr, request_context);
     return browser;
 }
 
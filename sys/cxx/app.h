@@ -53,9 +53,18 @@ public:
                                       PageObserver observer,
                                       void* ctx);
 
+    //
+    // Set the proxy of the global request context.
+    //
+    void SetProxy(const ProxyOptions* proxy);
+
     CefSettings cef_settings;
 private:
+    void SetProxyValue(CefRefPtr<CefValue> proxy);
+
     std::optional<std::string> _scheme_path = std::nullopt;
+    CefRefPtr<CefValue> _proxy = nullptr;
+    bool _is_initialized = false;
     CreateWebviewCallback _callback;
     void* _ctx;
 
@@ -20,6 +20,8 @@ IBrowser::IBrowser(PageOptions settings,
     , _ctx(ctx)
     , IRender(settings, observer, ctx)
     , IDisplay(settings, observer, ctx)
+    , IRequest(settings, observer, ctx)
+    , IDownload(settings, observer, ctx)
 {
 }
 
@@ -28,6 +30,26 @@ CefRefPtr<CefDragHandler> IBrowser::GetDragHandler()
     return this;
 }
 
+CefRefPtr<CefFocusHandler> IBrowser::GetFocusHandler()
+{
+    if (_is_closed)
+    {
+        return nullptr;
+    }
+
+    return this;
+}
+
+CefRefPtr<CefKeyboardHandler> IBrowser::GetKeyboardHandler()
+{
+    if (_is_closed)
+    {
+        return nullptr;
+    }
+
+    return this;
+}
+
 void IBrowser::OnBeforeContextMenu(CefRefPtr<CefBrowser> browser,
                                    CefRefPtr<CefFrame> frame,
                                    CefRefPtr<CefContextMenuParams> params,
@@ -68,6 +90,16 @@ CefRefPtr<CefDisplayHandler> IBrowser::GetDisplayHandler()
     return this;
 }
 
+CefRefPtr<CefDownloadHandler> IBrowser::GetDownloadHandler()
+{
+    if (_is_closed)
+    {
+        return nullptr;
+    }
+
+    return this;
+}
+
 CefRefPtr<CefLifeSpanHandler> IBrowser::GetLifeSpanHandler()
 {
     if (_is_closed)
@@ -98,6 +130,15 @@ CefRefPtr<CefRenderHandler> IBrowser::GetRenderHandler()
     return nullptr;
 }
 
+CefRefPtr<CefRequestHandler> IBrowser::GetRequestHandler()
+{
+    if (_is_closed)
+    {
+        return nullptr;
+    }
+
+    return this;
+}
 
 void IBrowser::OnLoadStart(CefRefPtr<CefBrowser> browser,
                            CefRefPtr<CefFrame> frame,
@@ -190,7 +231,102 @@ bool IBrowser::OnDragEnter(CefRefPtr<CefBrowser> browser,
                            CefRefPtr<CefDragData> dragData,
                            CefDragHandler::DragOperationsMask mask)
 {
-    return true;
+    // Offscreen pages only receive the drags sent by the host with
+    // page_drag_target_drag_enter, which returning true would cancel. Windowed
+    // pages keep rejecting the drags of the OS, e.g. files that would navigate
+    // the page to file://.
+    return !_settings.is_offscreen;
+}
+
+void IBrowser::OnTakeFocus(CefRefPtr<CefBrowser> browser, bool next)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return;
+    }
+
+    _observer.on_take_focus(next, _ctx);
+}
+
+bool IBrowser::OnSetFocus(CefRefPtr<CefBrowser> browser, CefFocusHandler::FocusSource source)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return false;
+    }
+
+    // FocusSource have the same value with cef_focus_source_t.
+    return _observer.on_set_focus((::FocusSource)source, _ctx);
+}
+
+void IBrowser::OnGotFocus(CefRefPtr<CefBrowser> browser)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return;
+    }
+
+    _observer.on_got_focus(_ctx);
+}
+
+static KeyEvent to_c(const CefKeyEvent& event)
+{
+    KeyEvent key_event;
+    // KeyEventType have the same value with cef_key_event_type_t.
+    key_event.type = (KeyEventType)event.type;
+    key_event.modifiers = event.modifiers;
+    key_event.windows_key_code = event.windows_key_code;
+    key_event.native_key_code = event.native_key_code;
+    key_event.is_system_key = event.is_system_key;
+    key_event.character = event.character;
+    key_event.unmodified_character = event.unmodified_character;
+    return key_event;
+}
+
+bool IBrowser::OnPreKeyEvent(CefRefPtr<CefBrowser> browser,
+                             const CefKeyEvent& event,
+                             CefEventHandle os_event,
+                             bool* is_keyboard_shortcut)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return false;
+    }
+
+    auto key_event = to_c(event);
+    switch (_observer.on_pre_key_event(&key_event, _ctx))
+    {
+        case kPreKeyEventShortcut:
+            *is_keyboard_shortcut = true;
+            return false;
+        case kPreKeyEventConsume:
+            return true;
+        default:
+            return false;
+    }
+}
+
+bool IBrowser::OnKeyEvent(CefRefPtr<CefBrowser> browser,
+                          const CefKeyEvent& event,
+                          CefEventHandle os_event)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return false;
+    }
+
+    auto key_event = to_c(event);
+    return _observer.on_key_event(&key_event, _ctx);
 }
 
 void IBrowser::OnBeforeClose(CefRefPtr<CefBrowser> browser)
@@ -202,6 +338,21 @@ void IBrowser::OnBeforeClose(CefRefPtr<CefBrowser> browser)
     _browser = std::nullopt;
 }
 
+void IBrowser::SetFocus(bool focus)
+{
+    if (_is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->SetFocus(focus);
+}
+
 void IBrowser::SetDevToolsOpenState(bool is_open)
 {
     if (_is_closed)
@@ -272,6 +423,45 @@ void IBrowser::ISendMessage(std::string message)
     _browser.value()->GetMainFrame()->SendProcessMessage(PID_RENDERER, msg);
 }
 
+void IBrowser::SetProxy(const ProxyOptions* proxy)
+{
+    if (_is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    SetProxyPreference(_browser.value()->GetHost()->GetRequestContext(), ProxyToValue(proxy));
+}
+
+void IBrowser::GetContentSize(uint32_t timeout, ContentSizeCallback callback, void* ctx)
+{
+    if (_is_closed || !_browser.has_value())
+    {
+        callback(false, 0, 0, ctx);
+        return;
+    }
+
+    ::GetContentSize(_browser.value(), timeout, callback, ctx);
+}
+
+void IBrowser::PrintToPdf(std::string path, const PdfOptions* options, PrintToPdfCallback callback, void* ctx)
+{
+    if (_is_closed || !_browser.has_value())
+    {
+        callback(false, ctx);
+        return;
+    }
+
+    _browser.value()->GetHost()->PrintToPDF(path,
+                                            ToPdfPrintSettings(options),
+                                            new IPdfPrintCallback(callback, ctx));
+}
+
 void IBrowser::IClose()
 {
     if (_is_closed)
@@ -287,6 +477,8 @@ void IBrowser::IClose()
     IRender::IClose();
     IDisplay::IClose();
     IControl::IClose();
+    IRequest::IClose();
+    IDownload::IClose();
     _browser.value()->GetHost()->CloseBrowser(true);
 
     _browser = std::nullopt;
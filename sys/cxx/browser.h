@@ -12,19 +12,27 @@
 #include <optional>
 
 #include "control.h"
+#include "devtools.h"
+#include "print.h"
 #include "display.h"
+#include "download.h"
 #include "include/cef_app.h"
 #include "render.h"
+#include "request.h"
 #include "webview.h"
 
 class IBrowser : public CefClient,
     public CefDragHandler,
+    public CefFocusHandler,
+    public CefKeyboardHandler,
     public CefContextMenuHandler,
     public CefLoadHandler,
     public CefLifeSpanHandler,
     public IControl,
     public IRender,
-    public IDisplay
+    public IDisplay,
+    public IRequest,
+    public IDownload
 {
 public:
     IBrowser(PageOptions settings,
@@ -51,11 +59,15 @@ public:
     /* CefClient */
 
     virtual CefRefPtr<CefDragHandler> GetDragHandler() override;
+    virtual CefRefPtr<CefFocusHandler> GetFocusHandler() override;
+    virtual CefRefPtr<CefKeyboardHandler> GetKeyboardHandler() override;
     virtual CefRefPtr<CefContextMenuHandler> GetContextMenuHandler() override;
     virtual CefRefPtr<CefDisplayHandler> GetDisplayHandler() override;
+    virtual CefRefPtr<CefDownloadHandler> GetDownloadHandler() override;
     virtual CefRefPtr<CefLifeSpanHandler> GetLifeSpanHandler() override;
     virtual CefRefPtr<CefLoadHandler> GetLoadHandler() override;
     virtual CefRefPtr<CefRenderHandler> GetRenderHandler() override;
+    virtual CefRefPtr<CefRequestHandler> GetRequestHandler() override;
     virtual bool OnProcessMessageReceived(CefRefPtr<CefBrowser> browser,
                                           CefRefPtr<CefFrame> frame,
                                           CefProcessId source_process,
@@ -100,10 +112,30 @@ public:
                              CefRefPtr<CefDragData> dragData,
                              CefDragHandler::DragOperationsMask mask) override;
 
+    /* CefFocusHandler */
+
+    virtual void OnTakeFocus(CefRefPtr<CefBrowser> browser, bool next) override;
+    virtual bool OnSetFocus(CefRefPtr<CefBrowser> browser, FocusSource source) override;
+    virtual void OnGotFocus(CefRefPtr<CefBrowser> browser) override;
+
+    /* CefKeyboardHandler */
+
+    virtual bool OnPreKeyEvent(CefRefPtr<CefBrowser> browser,
+                               const CefKeyEvent& event,
+                               CefEventHandle os_event,
+                               bool* is_keyboard_shortcut) override;
+    virtual bool OnKeyEvent(CefRefPtr<CefBrowser> browser,
+                            const CefKeyEvent& event,
+                            CefEventHandle os_event) override;
+
     void IClose();
     void SetDevToolsOpenState(bool is_open);
+    void SetFocus(bool focus);
     const void* GetHWND();
     void ISendMessage(std::string message);
+    void SetProxy(const ProxyOptions* proxy);
+    void GetContentSize(uint32_t timeout, ContentSizeCallback callback, void* ctx);
+    void PrintToPdf(std::string path, const PdfOptions* options, PrintToPdfCallback callback, void* ctx);
 private:
     std::optional<CefRefPtr<CefBrowser>> _browser = std::nullopt;
 
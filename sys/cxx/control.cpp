@@ -7,46 +7,8 @@
 
 #include "control.h"
 
-#ifdef WIN32
-#include "windows.h"
-#endif
-
-#define ENTER_CODE 28
-#define SPACE_CODE 57
-#define L_SHIFT_CODE 42
-#define R_SHIFT_CODE 54
-#define L_CTRL_CODE 29
-
-#define IS_ABC(x) (x >= 0x10 && x <= 0x37)
-#define IS_NUMBER(x) (x >= 2 && x <= 11)
-#define IS_ALLOW_CHAR_MODE(x) (x != L_SHIFT_CODE && x != R_SHIFT_CODE && x != L_CTRL_CODE)
-
-static const std::map<int, std::pair<char, char>> SYMBOL_REFS = {
-    {2, {'1', '!'}},   {3, {'2', '@'}},  {4, {'3', '#'}},   {5, {'4', '$'}},  {6, {'5', '%'}},
-    {7, {'6', '^'}},   {8, {'7', '&'}},  {9, {'8', '*'}},   {10, {'9', '('}}, {11, {'0', ')'}},
-    {12, {'-', '_'}},  {13, {'=', '+'}}, {26, {'[', '{'}},  {27, {']', '}'}}, {39, {';', ':'}},
-    {40, {'\'', '"'}}, {41, {'`', '~'}}, {43, {'\\', '|'}}, {51, {',', '<'}}, {52, {'.', '>'}},
-    {53, {'/', '?'}} };
-
-static bool is_symbol_from_code(int scan_code)
-{
-    constexpr std::array<int, 11> codes = { 26, 27, 43, 39, 40, 51, 52, 53, 41, 12, 13 };
-    for (int code : codes)
-    {
-        if (code == scan_code)
-        {
-            return true;
-        }
-    }
-
-    return false;
-}
-
-static std::optional<std::pair<char, char>> get_symbol_ref(int scan_code)
-{
-    auto iter = SYMBOL_REFS.find(scan_code);
-    return iter != SYMBOL_REFS.end() ? std::optional(iter->second) : std::nullopt;
-}
+#include <algorithm>
+#include <vector>
 
 CefBrowserHost::MouseButtonType from_c(MouseButtons button)
 {
@@ -64,40 +26,38 @@ CefBrowserHost::MouseButtonType from_c(MouseButtons button)
     }
 }
 
-cef_key_event_type_t from_c(bool pressed)
+uint32_t mouse_button_flag(MouseButtons button)
 {
-    if (pressed)
+    if (button == MouseButtons::kLeft)
     {
-        return cef_key_event_type_t::KEYEVENT_KEYDOWN;
+        return EVENTFLAG_LEFT_MOUSE_BUTTON;
+    }
+    else if (button == MouseButtons::kRight)
+    {
+        return EVENTFLAG_RIGHT_MOUSE_BUTTON;
     }
     else
     {
-        return cef_key_event_type_t::KEYEVENT_KEYUP;
+        return EVENTFLAG_MIDDLE_MOUSE_BUTTON;
     }
 }
 
-cef_event_flags_t from_c(Modifiers modifiers)
+CefRange from_c(const ImeRange* range)
 {
-    if (modifiers == Modifiers::kShift)
-    {
-        return cef_event_flags_t::EVENTFLAG_SHIFT_DOWN;
-    }
-    else if (modifiers == Modifiers::kCtrl)
-    {
-        return cef_event_flags_t::EVENTFLAG_CONTROL_DOWN;
-    }
-    else if (modifiers == Modifiers::kAlt)
-    {
-        return cef_event_flags_t::EVENTFLAG_ALT_DOWN;
-    }
-    else if (modifiers == Modifiers::kWin)
-    {
-        return cef_event_flags_t::EVENTFLAG_COMMAND_DOWN;
-    }
-    else
-    {
-        return cef_event_flags_t::EVENTFLAG_NONE;
-    }
+    return range ? CefRange(range->from, range->to) : CefRange::InvalidRange();
+}
+
+CefCompositionUnderline from_c(const ImeUnderline& underline)
+{
+    CefCompositionUnderline line;
+    line.range = CefRange(underline.range.from, underline.range.to);
+    line.color = underline.color;
+    line.background_color = underline.background_color;
+    line.thick = underline.thick;
+
+    // ImeUnderlineStyle have the same value with cef_composition_underline_style_t.
+    line.style = (cef_composition_underline_style_t)underline.style;
+    return line;
 }
 
 /* =================== IMEControl ================= */
@@ -107,29 +67,61 @@ void IMEControl::SetBrowser(CefRefPtr<CefBrowser> browser)
     _browser = browser;
 }
 
-void IMEControl::OnIMEComposition(std::string input)
+void IMEControl::OnIMESetComposition(std::string text,
+                                     const ImeUnderline* underlines,
+                                     size_t underlines_size,
+                                     const ImeRange* replacement_range,
+                                     const ImeRange* selection_range)
 {
     if (!_browser.has_value())
     {
         return;
     }
 
-    _browser.value()->GetHost()->ImeCommitText(input, CefRange::InvalidRange(), 0);
+    std::vector<CefCompositionUnderline> lines;
+    for (size_t i = 0; i < underlines_size; i++)
+    {
+        lines.push_back(from_c(underlines[i]));
+    }
+
+    _browser.value()->GetHost()->ImeSetComposition(text,
+                                                   lines,
+                                                   from_c(replacement_range),
+                                                   from_c(selection_range));
 }
 
-void IMEControl::OnIMESetComposition(std::string input, int x, int y)
+void IMEControl::OnIMECommitText(std::string text,
+                                 const ImeRange* replacement_range,
+                                 int relative_cursor_pos)
 {
     if (!_browser.has_value())
     {
         return;
     }
 
-    CefCompositionUnderline line;
-    line.style = CEF_CUS_DASH;
-    line.range = CefRange(0, y);
+    _browser.value()->GetHost()->ImeCommitText(text,
+                                               from_c(replacement_range),
+                                               relative_cursor_pos);
+}
+
+void IMEControl::OnIMEFinishComposingText(bool keep_selection)
+{
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->ImeFinishComposingText(keep_selection);
+}
+
+void IMEControl::OnIMECancelComposition()
+{
+    if (!_browser.has_value())
+    {
+        return;
+    }
 
-    _browser.value()->GetHost()->ImeSetComposition(input, { line }, CefRange::InvalidRange(),
-                                                   CefRange(x, y));
+    _browser.value()->GetHost()->ImeCancelComposition();
 }
 
 void IMEControl::IClose()
@@ -146,7 +138,7 @@ void IControl::SetBrowser(CefRefPtr<CefBrowser> browser)
     IMEControl::SetBrowser(browser);
 }
 
-void IControl::OnMouseClick(MouseButtons button, bool pressed)
+void IControl::OnMouseClick(MouseButtons button, bool pressed, int click_count, uint32_t modifiers)
 {
     if (_is_closed)
     {
@@ -158,23 +150,50 @@ void IControl::OnMouseClick(MouseButtons button, bool pressed)
         return;
     }
 
-    if (button == MouseButtons::kLeft)
+    // CEF can not send the back and forward buttons to the page, navigate
+    // on release like the browsers do instead.
+    if (button == MouseButtons::kBack || button == MouseButtons::kForward)
     {
-        _mouse_event.modifiers = EVENTFLAG_LEFT_MOUSE_BUTTON;
+        auto browser = _browser.value();
+        if (pressed)
+        {
+            return;
+        }
+        else if (button == MouseButtons::kBack && browser->CanGoBack())
+        {
+            browser->GoBack();
+        }
+        else if (button == MouseButtons::kForward && browser->CanGoForward())
+        {
+            browser->GoForward();
+        }
+
+        return;
     }
-    else if (button == MouseButtons::kMiddle)
+
+    uint32_t flag = mouse_button_flag(button);
+    if (pressed)
     {
-        _mouse_event.modifiers = EVENTFLAG_MIDDLE_MOUSE_BUTTON;
+        _mouse_buttons |= flag;
     }
-    else if (button == MouseButtons::kRight)
+    else
     {
-        _mouse_event.modifiers = EVENTFLAG_RIGHT_MOUSE_BUTTON;
+        _mouse_buttons &= ~flag;
     }
 
-    _browser.value()->GetHost()->SendMouseClickEvent(_mouse_event, from_c(button), !pressed, 1);
+    _mouse_event.modifiers = modifiers | _mouse_buttons;
+    _browser.value()->GetHost()->SendMouseClickEvent(_mouse_event,
+                                                     from_c(button),
+                                                     !pressed,
+                                                     std::max(click_count, 1));
 }
 
-void IControl::OnMouseClickWithPosition(MouseButtons button, int x, int y, bool pressed)
+void IControl::OnMouseClickWithPosition(MouseButtons button,
+                                        int x,
+                                        int y,
+                                        bool pressed,
+                                        int click_count,
+                                        uint32_t modifiers)
 {
     if (_is_closed)
     {
@@ -186,25 +205,12 @@ void IControl::OnMouseClickWithPosition(MouseButtons button, int x, int y, bool
         return;
     }
 
-    if (button == MouseButtons::kLeft)
-    {
-        _mouse_event.modifiers = EVENTFLAG_LEFT_MOUSE_BUTTON;
-    }
-    else if (button == MouseButtons::kMiddle)
-    {
-        _mouse_event.modifiers = EVENTFLAG_MIDDLE_MOUSE_BUTTON;
-    }
-    else if (button == MouseButtons::kRight)
-    {
-        _mouse_event.modifiers = EVENTFLAG_RIGHT_MOUSE_BUTTON;
-    }
-
     _mouse_event.x = x;
     _mouse_event.y = y;
-    _browser.value()->GetHost()->SendMouseClickEvent(_mouse_event, from_c(button), !pressed, 1);
+    OnMouseClick(button, pressed, click_count, modifiers);
 }
 
-void IControl::OnMouseMove(int x, int y)
+void IControl::OnMouseMove(int x, int y, uint32_t modifiers)
 {
     if (_is_closed)
     {
@@ -218,10 +224,11 @@ void IControl::OnMouseMove(int x, int y)
 
     _mouse_event.x = x;
     _mouse_event.y = y;
+    _mouse_event.modifiers = modifiers | _mouse_buttons;
     _browser.value()->GetHost()->SendMouseMoveEvent(_mouse_event, false);
 }
 
-void IControl::OnMouseWheel(int x, int y)
+void IControl::OnMouseWheel(int x, int y, int delta_x, int delta_y, uint32_t modifiers)
 {
     if (_is_closed)
     {
@@ -233,10 +240,13 @@ void IControl::OnMouseWheel(int x, int y)
         return;
     }
 
-    _browser.value()->GetHost()->SendMouseWheelEvent(_mouse_event, x, y);
+    _mouse_event.x = x;
+    _mouse_event.y = y;
+    _mouse_event.modifiers = modifiers | _mouse_buttons;
+    _browser.value()->GetHost()->SendMouseWheelEvent(_mouse_event, delta_x, delta_y);
 }
 
-void IControl::OnKeyboard(int scan_code, bool pressed, Modifiers modifiers)
+void IControl::OnMouseLeave(uint32_t modifiers)
 {
     if (_is_closed)
     {
@@ -248,79 +258,36 @@ void IControl::OnKeyboard(int scan_code, bool pressed, Modifiers modifiers)
         return;
     }
 
-#ifdef WIN32
-    auto windows_key_code = MapVirtualKeyA(scan_code, MAPVK_VSC_TO_VK);
-    bool is_capslock_on = (GetKeyState(VK_CAPITAL) & 0x0001) != 0;
-#elif LINUX
-    bool is_capslock_on = false;
-    if (_display)
-    {
-        XKeyboardState state;
-        XGetKeyboardControl(_display, &state);
-        is_capslock_on = state.led_mask & (1 << 1);
-        XCloseDisplay(_display);
-    }
-#else
-    bool is_capslock_on = false;
-#endif
-
-    bool is_az = IS_ABC(scan_code);                   // a-z
-    bool is_number = IS_NUMBER(scan_code);            // 0-9
-    bool is_symbol = is_symbol_from_code(scan_code);  // []\;',./`-=
-
-    CefKeyEvent event;
-    event.type = from_c(pressed);
-    event.native_key_code = scan_code;
-    event.modifiers = from_c(modifiers);
-#ifdef WIN32
-    event.windows_key_code = windows_key_code;
-#endif
-
-    _browser.value()->GetHost()->SendKeyEvent(event);
-    if (!pressed)
-    {
-        return;
-    }
+    _mouse_event.modifiers = modifiers | _mouse_buttons;
+    _browser.value()->GetHost()->SendMouseMoveEvent(_mouse_event, true);
+}
 
-    // not allow to char mode
-    if (!IS_ALLOW_CHAR_MODE(scan_code))
+void IControl::OnKeyEvent(const KeyEvent* event)
+{
+    if (_is_closed)
     {
         return;
     }
 
-    if (!is_capslock_on && is_az && scan_code != ENTER_CODE)
+    if (!_browser.has_value())
     {
-    #ifdef WIN32
-        event.windows_key_code += 32;
-    #endif
+        return;
     }
 
-    if (is_number && modifiers == Modifiers::kShift)
-    {
-        event.windows_key_code = get_symbol_ref(scan_code).value().second;
-    }
-    else if (is_symbol && modifiers == Modifiers::kShift)
-    {
-        event.windows_key_code = get_symbol_ref(scan_code).value().second;
-    }
-    else if (is_symbol)
-    {
-        event.windows_key_code = get_symbol_ref(scan_code).value().first;
-    }
+    // KeyEventType have the same value with cef_key_event_type_t.
+    CefKeyEvent key_event;
+    key_event.type = (cef_key_event_type_t)event->type;
+    key_event.modifiers = event->modifiers;
+    key_event.windows_key_code = event->windows_key_code;
+    key_event.native_key_code = event->native_key_code;
+    key_event.is_system_key = event->is_system_key;
+    key_event.character = event->character;
+    key_event.unmodified_character = event->unmodified_character;
 
-    // allow to char model
-    if (is_az || is_number || is_symbol || scan_code == SPACE_CODE)
-    {
-        event.type = KEYEVENT_CHAR;
-        _browser.value()->GetHost()->SendKeyEvent(event);
-    }
+    _browser.value()->GetHost()->SendKeyEvent(key_event);
 }
 
-void IControl::OnTouch(int id,
-                       int x,
-                       int y,
-                       cef_touch_event_type_t type,
-                       cef_pointer_type_t pointer_type)
+void IControl::OnTouch(const TouchEvent* event)
 {
     if (_is_closed)
     {
@@ -332,15 +299,23 @@ void IControl::OnTouch(int id,
         return;
     }
 
-    CefTouchEvent event;
+    CefTouchEvent touch_event;
+
+    touch_event.id = event->id;
+    touch_event.x = event->x;
+    touch_event.y = event->y;
+    touch_event.radius_x = event->radius_x;
+    touch_event.radius_y = event->radius_y;
+    touch_event.rotation_angle = event->rotation_angle;
+    touch_event.pressure = event->pressure;
+    touch_event.modifiers = event->modifiers;
 
-    event.id = id;
-    event.x = x;
-    event.y = y;
-    event.type = type;
-    event.pointer_type = pointer_type;
+    // TouchEventType have the same value with cef_touch_event_type_t.
+    // Same as TouchPointerType.
+    touch_event.type = (cef_touch_event_type_t)event->type;
+    touch_event.pointer_type = (cef_pointer_type_t)event->pointer_type;
 
-    _browser.value()->GetHost()->SendTouchEvent(event);
+    _browser.value()->GetHost()->SendTouchEvent(touch_event);
 }
 
 void IControl::IClose()
@@ -8,10 +8,6 @@
 #include "include/cef_app.h"
 #include "webview.h"
 
-#ifdef CEF_X11
-#include <X11/XKBlib.h>
-#endif
-
 class IMEControl
 {
 public:
@@ -21,8 +17,14 @@ public:
     }
 
     void SetBrowser(CefRefPtr<CefBrowser> browser);
-    void OnIMEComposition(std::string input);
-    void OnIMESetComposition(std::string input, int x, int y);
+    void OnIMESetComposition(std::string text,
+                             const ImeUnderline* underlines,
+                             size_t underlines_size,
+                             const ImeRange* replacement_range,
+                             const ImeRange* selection_range);
+    void OnIMECommitText(std::string text, const ImeRange* replacement_range, int relative_cursor_pos);
+    void OnIMEFinishComposingText(bool keep_selection);
+    void OnIMECancelComposition();
     void IClose();
 
 private:
@@ -39,21 +41,26 @@ public:
     }
 
     void SetBrowser(CefRefPtr<CefBrowser> browser);
-    void OnKeyboard(int scan_code, bool pressed, Modifiers modifiers);
-    void OnMouseClick(MouseButtons button, bool pressed);
-    void OnMouseClickWithPosition(MouseButtons button, int x, int y, bool pressed);
-    void OnMouseMove(int x, int y);
-    void OnMouseWheel(int x, int y);
-    void OnTouch(int id, int x, int y, cef_touch_event_type_t type, cef_pointer_type_t pointer_type);
+    void OnKeyEvent(const KeyEvent* event);
+    void OnMouseClick(MouseButtons button, bool pressed, int click_count, uint32_t modifiers);
+    void OnMouseClickWithPosition(MouseButtons button,
+                                  int x,
+                                  int y,
+                                  bool pressed,
+                                  int click_count,
+                                  uint32_t modifiers);
+    void OnMouseMove(int x, int y, uint32_t modifiers);
+    void OnMouseWheel(int x, int y, int delta_x, int delta_y, uint32_t modifiers);
+    void OnMouseLeave(uint32_t modifiers);
+    void OnTouch(const TouchEvent* event);
     void IClose();
 
 private:
     std::optional<CefRefPtr<CefBrowser>> _browser = std::nullopt;
 
-#ifdef CEF_X11
-    Display* _display = XOpenDisplay(nullptr);
-#endif
     CefMouseEvent _mouse_event;
+    // The mouse buttons currently held down, as cef_event_flags_t.
+    uint32_t _mouse_buttons = EVENTFLAG_NONE;
     bool _is_closed = false;
 };
 
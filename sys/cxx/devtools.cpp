@@ -0,0 +1,99 @@
+//
+//  devtools.cpp
+//  webview
+//
+
+#include "devtools.h"
+
+#include <math.h>
+
+#include "include/base/cef_callback.h"
+#include "include/cef_parser.h"
+#include "include/wrapper/cef_closure_task.h"
+#include "include/wrapper/cef_helpers.h"
+
+void GetContentSize(CefRefPtr<CefBrowser> browser, uint32_t timeout, ContentSizeCallback callback, void* ctx)
+{
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI, base::BindOnce(&GetContentSize, browser, timeout, callback, ctx));
+        return;
+    }
+
+    CefRefPtr<IContentSizeObserver> observer = new IContentSizeObserver(callback, ctx);
+    observer->Execute(browser, timeout);
+}
+
+/* =================== IContentSizeObserver ================= */
+
+IContentSizeObserver::IContentSizeObserver(ContentSizeCallback callback, void* ctx)
+    : _callback(callback), _ctx(ctx)
+{
+}
+
+void IContentSizeObserver::Execute(CefRefPtr<CefBrowser> browser, uint32_t timeout)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    // The registration keeps the observer alive until the result is received.
+    _registration = browser->GetHost()->AddDevToolsMessageObserver(this);
+    _message_id = browser->GetHost()->ExecuteDevToolsMethod(0, "Page.getLayoutMetrics", nullptr);
+    if (_message_id == 0)
+    {
+        Done(false, 0, 0);
+        return;
+    }
+
+    // The result may never arrive, e.g. when the page is closed meanwhile, so
+    // give up after the timeout to release the registration and the callback.
+    CefPostDelayedTask(TID_UI,
+                       base::BindOnce(&IContentSizeObserver::Done, CefRefPtr<IContentSizeObserver>(this), false, 0, 0),
+                       timeout);
+}
+
+void IContentSizeObserver::OnDevToolsMethodResult(CefRefPtr<CefBrowser> browser,
+                                                  int message_id,
+                                                  bool success,
+                                                  const void* result,
+                                                  size_t result_size)
+{
+    if (message_id != _message_id)
+    {
+        return;
+    }
+
+    if (!success)
+    {
+        Done(false, 0, 0);
+        return;
+    }
+
+    CefRefPtr<CefValue> value = CefParseJSON(result, result_size, JSON_PARSER_RFC);
+    if (!value || value->GetType() != VTYPE_DICTIONARY)
+    {
+        Done(false, 0, 0);
+        return;
+    }
+
+    // cssContentSize is in CSS pixels, which are view coordinates.
+    CefRefPtr<CefDictionaryValue> size = value->GetDictionary()->GetDictionary("cssContentSize");
+    if (!size)
+    {
+        Done(false, 0, 0);
+        return;
+    }
+
+    Done(true, (int)ceil(size->GetDouble("width")), (int)ceil(size->GetDouble("height")));
+}
+
+void IContentSizeObserver::Done(bool success, int width, int height)
+{
+    if (_is_done)
+    {
+        return;
+    }
+
+    _is_done = true;
+    _callback(success, width, height, _ctx);
+    _registration = nullptr;
+}
@@ -0,0 +1,51 @@
+//
+//  devtools.h
+//  webview
+//
+
+#ifndef LIBWEBVIEW_DEVTOOLS_H
+#define LIBWEBVIEW_DEVTOOLS_H
+#pragma once
+
+#include "include/cef_app.h"
+#include "include/cef_devtools_message_observer.h"
+#include "webview.h"
+
+// Get the size of the page content in view coordinates via the
+// Page.getLayoutMetrics DevTools method. The callback is called exactly once,
+// with success set to false if no result is received within |timeout|
+// milliseconds.
+void GetContentSize(CefRefPtr<CefBrowser> browser, uint32_t timeout, ContentSizeCallback callback, void* ctx);
+
+class IContentSizeObserver : public CefDevToolsMessageObserver
+{
+public:
+    IContentSizeObserver(ContentSizeCallback callback, void* ctx);
+    ~IContentSizeObserver()
+    {
+        Done(false, 0, 0);
+    }
+
+    /* CefDevToolsMessageObserver */
+
+    virtual void OnDevToolsMethodResult(CefRefPtr<CefBrowser> browser,
+                                        int message_id,
+                                        bool success,
+                                        const void* result,
+                                        size_t result_size) override;
+
+    void Execute(CefRefPtr<CefBrowser> browser, uint32_t timeout);
+
+private:
+    ContentSizeCallback _callback;
+    void* _ctx;
+    int _message_id = 0;
+    bool _is_done = false;
+    CefRefPtr<CefRegistration> _registration;
+
+    void Done(bool success, int width, int height);
+
+    IMPLEMENT_REFCOUNTING(IContentSizeObserver);
+};
+
+#endif  // LIBWEBVIEW_DEVTOOLS_H
@@ -66,6 +66,43 @@ void IDisplay::OnFullscreenModeChange(CefRefPtr<CefBrowser> browser, bool fullsc
     }
 };
 
+bool IDisplay::OnCursorChange(CefRefPtr<CefBrowser> browser,
+                              CefCursorHandle cursor,
+                              cef_cursor_type_t type,
+                              const CefCursorInfo& custom_cursor_info)
+{
+    CEF_REQUIRE_UI_THREAD();
+    if (_is_closed)
+    {
+        return false;
+    }
+
+    // Windowed pages keep the default handling, which sets the native cursor.
+    if (!_settings.is_offscreen)
+    {
+        return false;
+    }
+
+    if (type == CT_CUSTOM)
+    {
+        CursorInfo info;
+        info.hotspot_x = custom_cursor_info.hotspot.x;
+        info.hotspot_y = custom_cursor_info.hotspot.y;
+        info.image_scale_factor = custom_cursor_info.image_scale_factor;
+        info.buffer = custom_cursor_info.buffer;
+        info.width = custom_cursor_info.size.width;
+        info.height = custom_cursor_info.size.height;
+
+        _observer.on_cursor_change(CursorType::kCursorCustom, &info, _ctx);
+    }
+    else
+    {
+        _observer.on_cursor_change((CursorType)type, nullptr, _ctx);
+    }
+
+    return true;
+}
+
 void IDisplay::IClose()
 {
     _is_closed = true;
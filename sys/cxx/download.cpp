@@ -0,0 +1,120 @@
+//
+//  download.cpp
+//  webview
+//
+
+#include "download.h"
+
+#include "include/wrapper/cef_helpers.h"
+
+static DownloadState get_download_state(CefRefPtr<CefDownloadItem> item)
+{
+    if (item->IsComplete())
+    {
+        return DownloadState::kDownloadComplete;
+    }
+    else if (item->IsCanceled())
+    {
+        return DownloadState::kDownloadCanceled;
+    }
+    else if (item->IsInterrupted())
+    {
+        return DownloadState::kDownloadInterrupted;
+    }
+    else
+    {
+        return DownloadState::kDownloadInProgress;
+    }
+}
+
+// The strings of the item are owned by the caller and only valid during the
+// observer callback.
+typedef struct
+{
+    std::string url;
+    std::string original_url;
+    std::string suggested_file_name;
+    std::string full_path;
+    std::string mime_type;
+} DownloadItemStrings;
+
+static DownloadItem to_c(CefRefPtr<CefDownloadItem> item, DownloadItemStrings& strings)
+{
+    strings.url = item->GetURL().ToString();
+    strings.original_url = item->GetOriginalUrl().ToString();
+    strings.suggested_file_name = item->GetSuggestedFileName().ToString();
+    strings.full_path = item->GetFullPath().ToString();
+    strings.mime_type = item->GetMimeType().ToString();
+
+    DownloadItem output;
+    output.id = item->GetId();
+    output.url = strings.url.c_str();
+    output.original_url = strings.original_url.c_str();
+    output.suggested_file_name = strings.suggested_file_name.c_str();
+    output.full_path = strings.full_path.c_str();
+    output.mime_type = strings.mime_type.c_str();
+    output.received_bytes = item->GetReceivedBytes();
+    output.total_bytes = item->GetTotalBytes();
+    output.current_speed = item->GetCurrentSpeed();
+    output.percent_complete = item->GetPercentComplete();
+    output.state = get_download_state(item);
+    return output;
+}
+
+IDownload::IDownload(PageOptions settings, PageObserver observer, void* ctx)
+    : _settings(settings)
+    , _observer(observer)
+    , _ctx(ctx)
+{
+}
+
+void IDownload::OnBeforeDownload(CefRefPtr<CefBrowser> browser,
+                                 CefRefPtr<CefDownloadItem> download_item,
+                                 const CefString& suggested_name,
+                                 CefRefPtr<CefBeforeDownloadCallback> callback)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    // The download is canceled if the callback is never continued.
+    if (_is_closed)
+    {
+        return;
+    }
+
+    DownloadItemStrings strings;
+    DownloadItem item = to_c(download_item, strings);
+    strings.suggested_file_name = suggested_name.ToString();
+    item.suggested_file_name = strings.suggested_file_name.c_str();
+
+    // The observer owns the callback until it continues or cancels it.
+    BeforeDownloadCallback* before_download = new BeforeDownloadCallback;
+    before_download->ref = callback;
+
+    _observer.on_before_download(&item, before_download, _ctx);
+}
+
+void IDownload::OnDownloadUpdated(CefRefPtr<CefBrowser> browser,
+                                  CefRefPtr<CefDownloadItem> download_item,
+                                  CefRefPtr<CefDownloadItemCallback> callback)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return;
+    }
+
+    DownloadItemStrings strings;
+    DownloadItem item = to_c(download_item, strings);
+
+    // The observer owns the callback until it releases it.
+    DownloadCallback* download = new DownloadCallback;
+    download->ref = callback;
+
+    _observer.on_download_updated(&item, download, _ctx);
+}
+
+void IDownload::IClose()
+{
+    _is_closed = true;
+}
@@ -0,0 +1,53 @@
+//
+//  download.h
+//  webview
+//
+
+#ifndef LIBWEBVIEW_DOWNLOAD_H
+#define LIBWEBVIEW_DOWNLOAD_H
+#pragma once
+
+#include "include/cef_app.h"
+#include "webview.h"
+
+typedef struct
+{
+    CefRefPtr<CefBeforeDownloadCallback> ref;
+} BeforeDownloadCallback;
+
+typedef struct
+{
+    CefRefPtr<CefDownloadItemCallback> ref;
+} DownloadCallback;
+
+class IDownload : public CefDownloadHandler
+{
+public:
+    IDownload(PageOptions settings, PageObserver observer, void* ctx);
+    ~IDownload()
+    {
+        IClose();
+    }
+
+    /* CefDownloadHandler */
+
+    virtual void OnBeforeDownload(CefRefPtr<CefBrowser> browser,
+                                  CefRefPtr<CefDownloadItem> download_item,
+                                  const CefString& suggested_name,
+                                  CefRefPtr<CefBeforeDownloadCallback> callback) override;
+    virtual void OnDownloadUpdated(CefRefPtr<CefBrowser> browser,
+                                   CefRefPtr<CefDownloadItem> download_item,
+                                   CefRefPtr<CefDownloadItemCallback> callback) override;
+
+    void IClose();
+
+private:
+    bool _is_closed = false;
+    PageOptions _settings;
+    PageObserver _observer;
+    void* _ctx;
+
+    IMPLEMENT_REFCOUNTING(IDownload);
+};
+
+#endif  // LIBWEBVIEW_DOWNLOAD_H
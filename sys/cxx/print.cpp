@@ -0,0 +1,76 @@
+//
+//  print.cpp
+//  webview
+//
+
+#include "print.h"
+
+static cef_pdf_print_margin_type_t to_margin_type(PdfMarginType type)
+{
+    switch (type)
+    {
+    case PdfMarginType::kPdfMarginNone:
+        return PDF_PRINT_MARGIN_NONE;
+    case PdfMarginType::kPdfMarginCustom:
+        return PDF_PRINT_MARGIN_CUSTOM;
+    default:
+        return PDF_PRINT_MARGIN_DEFAULT;
+    }
+}
+
+CefPdfPrintSettings ToPdfPrintSettings(const PdfOptions* options)
+{
+    CefPdfPrintSettings settings;
+    settings.landscape = options->landscape;
+    settings.print_background = options->print_background;
+    settings.scale = options->scale;
+    settings.paper_width = options->paper_width;
+    settings.paper_height = options->paper_height;
+    settings.prefer_css_page_size = options->prefer_css_page_size;
+    settings.margin_type = to_margin_type(options->margin_type);
+    settings.margin_top = options->margin_top;
+    settings.margin_right = options->margin_right;
+    settings.margin_bottom = options->margin_bottom;
+    settings.margin_left = options->margin_left;
+    settings.display_header_footer = options->display_header_footer;
+
+    if (options->page_ranges != nullptr)
+    {
+        CefString(&settings.page_ranges) = options->page_ranges;
+    }
+
+    if (options->header_template != nullptr)
+    {
+        CefString(&settings.header_template) = options->header_template;
+    }
+
+    if (options->footer_template != nullptr)
+    {
+        CefString(&settings.footer_template) = options->footer_template;
+    }
+
+    return settings;
+}
+
+/* =================== IPdfPrintCallback ================= */
+
+IPdfPrintCallback::IPdfPrintCallback(PrintToPdfCallback callback, void* ctx)
+    : _callback(callback), _ctx(ctx)
+{
+}
+
+void IPdfPrintCallback::OnPdfPrintFinished(const CefString& path, bool ok)
+{
+    Done(ok);
+}
+
+void IPdfPrintCallback::Done(bool success)
+{
+    if (_is_done)
+    {
+        return;
+    }
+
+    _is_done = true;
+    _callback(success, _ctx);
+}
@@ -0,0 +1,38 @@
+//
+//  print.h
+//  webview
+//
+
+#ifndef LIBWEBVIEW_PRINT_H
+#define LIBWEBVIEW_PRINT_H
+#pragma once
+
+#include "include/cef_app.h"
+#include "webview.h"
+
+CefPdfPrintSettings ToPdfPrintSettings(const PdfOptions* options);
+
+class IPdfPrintCallback : public CefPdfPrintCallback
+{
+public:
+    IPdfPrintCallback(PrintToPdfCallback callback, void* ctx);
+    ~IPdfPrintCallback()
+    {
+        Done(false);
+    }
+
+    /* CefPdfPrintCallback */
+
+    virtual void OnPdfPrintFinished(const CefString& path, bool ok) override;
+
+private:
+    PrintToPdfCallback _callback;
+    void* _ctx;
+    bool _is_done = false;
+
+    void Done(bool success);
+
+    IMPLEMENT_REFCOUNTING(IPdfPrintCallback);
+};
+
+#endif  // LIBWEBVIEW_PRINT_H
@@ -8,6 +8,100 @@
 #include "render.h"
 
 #include <float.h>
+#include <string.h>
+
+#include <algorithm>
+#include <string>
+
+#include "include/base/cef_callback.h"
+#include "include/wrapper/cef_closure_task.h"
+
+typedef struct
+{
+    std::string link_url;
+    std::string link_title;
+    std::string fragment_text;
+    std::string fragment_html;
+    std::string fragment_base_url;
+    std::vector<std::string> file_paths;
+    std::vector<const char*> file_path_ptrs;
+} DragDataStrings;
+
+static DragData to_c(CefRefPtr<CefDragData> data, DragDataStrings& strings)
+{
+    strings.link_url = data->GetLinkURL().ToString();
+    strings.link_title = data->GetLinkTitle().ToString();
+    strings.fragment_text = data->GetFragmentText().ToString();
+    strings.fragment_html = data->GetFragmentHtml().ToString();
+    strings.fragment_base_url = data->GetFragmentBaseURL().ToString();
+
+    std::vector<CefString> file_names;
+    data->GetFileNames(file_names);
+    for (auto& it : file_names)
+    {
+        strings.file_paths.push_back(it.ToString());
+    }
+
+    for (auto& it : strings.file_paths)
+    {
+        strings.file_path_ptrs.push_back(it.c_str());
+    }
+
+    DragData output;
+    output.link_url = data->IsLink() ? strings.link_url.c_str() : nullptr;
+    output.link_title = data->IsLink() ? strings.link_title.c_str() : nullptr;
+    output.fragment_text = data->IsFragment() ? strings.fragment_text.c_str() : nullptr;
+    output.fragment_html = data->IsFragment() ? strings.fragment_html.c_str() : nullptr;
+    output.fragment_base_url = data->IsFragment() ? strings.fragment_base_url.c_str() : nullptr;
+    output.file_paths = strings.file_path_ptrs.data();
+    output.file_paths_size = strings.file_path_ptrs.size();
+    return output;
+}
+
+static CefRefPtr<CefDragData> from_c(const DragData* data)
+{
+    CefRefPtr<CefDragData> output = CefDragData::Create();
+    if (data->link_url != nullptr)
+    {
+        output->SetLinkURL(data->link_url);
+    }
+
+    if (data->link_title != nullptr)
+    {
+        output->SetLinkTitle(data->link_title);
+    }
+
+    if (data->fragment_text != nullptr)
+    {
+        output->SetFragmentText(data->fragment_text);
+    }
+
+    if (data->fragment_html != nullptr)
+    {
+        output->SetFragmentHtml(data->fragment_html);
+    }
+
+    if (data->fragment_base_url != nullptr)
+    {
+        output->SetFragmentBaseURL(data->fragment_base_url);
+    }
+
+    for (size_t i = 0; i < data->file_paths_size; i++)
+    {
+        output->AddFile(data->file_paths[i], CefString());
+    }
+
+    return output;
+}
+
+static CefMouseEvent mouse_event(int x, int y)
+{
+    CefMouseEvent event;
+    event.x = x;
+    event.y = y;
+    event.modifiers = 0;
+    return event;
+}
 
 IRender::IRender(PageOptions settings, PageObserver observer, void* ctx)
     : _settings(settings)
@@ -27,17 +121,21 @@ void IRender::OnImeCompositionRangeChanged(CefRefPtr<CefBrowser> browser,
                                            const CefRange& selected_range,
                                            const RectList& character_bounds)
 {
-    if (character_bounds.size() == 0)
+    if (is_closed)
     {
         return;
     }
 
-    Rect rect;
-    rect.x = character_bounds[0].x;
-    rect.y = character_bounds[0].y;
-    rect.width = character_bounds[0].width;
-    rect.height = character_bounds[0].height;
-    _observer.on_ime_rect(rect, _ctx);
+    std::vector<Rect> rects;
+    for (auto& it : character_bounds)
+    {
+        rects.push_back(Rect{ it.x, it.y, it.width, it.height });
+    }
+
+    _observer.on_ime_composition_range_changed(ImeRange{ selected_range.from, selected_range.to },
+                                               rects.data(),
+                                               rects.size(),
+                                               _ctx);
 }
 
 void IRender::GetViewRect(CefRefPtr<CefBrowser> browser, CefRect& rect)
@@ -67,7 +165,192 @@ void IRender::OnPaint(CefRefPtr<CefBrowser> browser,
         return;
     }
 
-    _observer.on_frame(buffer, width, height, _ctx);
+    std::vector<Rect> rects;
+    for (auto& it : dirtyRects)
+    {
+        rects.push_back(Rect{ it.x, it.y, it.width, it.height });
+    }
+
+    if (type == PET_POPUP)
+    {
+        size_t size = (size_t)width * (size_t)height * 4;
+        _popup_buffer.assign((const uint8_t*)buffer, (const uint8_t*)buffer + size);
+        _popup_width = width;
+        _popup_height = height;
+
+        rects.clear();
+        PaintWithPopup(rects);
+        return;
+    }
+
+    if (!_is_popup_visible)
+    {
+        _observer.on_frame(buffer,
+                           width,
+                           height,
+                           width * 4,
+                           _settings.device_scale_factor,
+                           rects.data(),
+                           rects.size(),
+                           _begin_frame,
+                           _ctx);
+        return;
+    }
+
+    size_t size = (size_t)width * (size_t)height * 4;
+    _view_buffer.assign((const uint8_t*)buffer, (const uint8_t*)buffer + size);
+    _view_width = width;
+    _view_height = height;
+
+    PaintWithPopup(rects);
+}
+
+void IRender::PaintWithPopup(std::vector<Rect>& rects)
+{
+    if (_view_buffer.empty())
+    {
+        return;
+    }
+
+    _frame_buffer = _view_buffer;
+
+    // The popup rect is in view coordinates, the buffers are in pixels.
+    int x = (int)(_popup_rect.x * _settings.device_scale_factor);
+    int y = (int)(_popup_rect.y * _settings.device_scale_factor);
+
+    int left = std::max(x, 0);
+    int top = std::max(y, 0);
+    int right = std::min(x + _popup_width, _view_width);
+    int bottom = std::min(y + _popup_height, _view_height);
+
+    if (!_popup_buffer.empty() && right > left)
+    {
+        for (int row = top; row < bottom; row++)
+        {
+            memcpy(_frame_buffer.data() + ((size_t)row * _view_width + left) * 4,
+                   _popup_buffer.data() + ((size_t)(row - y) * _popup_width + (left - x)) * 4,
+                   (size_t)(right - left) * 4);
+        }
+
+        rects.push_back(Rect{ left, top, right - left, bottom - top });
+    }
+
+    _observer.on_frame(_frame_buffer.data(),
+                       _view_width,
+                       _view_height,
+                       _view_width * 4,
+                       _settings.device_scale_factor,
+                       rects.data(),
+                       rects.size(),
+                       _begin_frame,
+                       _ctx);
+}
+
+void IRender::OnPopupShow(CefRefPtr<CefBrowser> browser, bool show)
+{
+    if (is_closed)
+    {
+        return;
+    }
+
+    _is_popup_visible = show;
+    if (show)
+    {
+        return;
+    }
+
+    // Release the buffers and repaint the view area under the popup.
+    _popup_rect = CefRect();
+    _view_buffer.clear();
+    _popup_buffer.clear();
+    _frame_buffer.clear();
+    browser->GetHost()->Invalidate(PET_VIEW);
+
+    _observer.on_popup_change(false, Rect{ 0, 0, 0, 0 }, _ctx);
+}
+
+bool IRender::StartDragging(CefRefPtr<CefBrowser> browser,
+                            CefRefPtr<CefDragData> drag_data,
+                            DragOperationsMask allowed_ops,
+                            int x,
+                            int y)
+{
+    if (is_closed)
+    {
+        return false;
+    }
+
+    DragDataStrings strings;
+    DragData data = to_c(drag_data, strings);
+
+    std::vector<uint8_t> image_buffer;
+    DragImage image;
+    bool has_image = false;
+    if (drag_data->HasImage())
+    {
+        int width = 0;
+        int height = 0;
+        CefRefPtr<CefBinaryValue> bitmap = drag_data->GetImage()->GetAsBitmap(
+            _settings.device_scale_factor, CEF_COLOR_TYPE_BGRA_8888, CEF_ALPHA_TYPE_PREMULTIPLIED, width, height);
+        if (bitmap)
+        {
+            image_buffer.resize(bitmap->GetSize());
+            bitmap->GetData(image_buffer.data(), image_buffer.size(), 0);
+
+            CefPoint hotspot = drag_data->GetImageHotspot();
+            image.hotspot_x = hotspot.x;
+            image.hotspot_y = hotspot.y;
+            image.buffer = image_buffer.data();
+            image.width = width;
+            image.height = height;
+            has_image = true;
+        }
+    }
+
+    if (!_observer.on_start_dragging(&data, has_image ? &image : nullptr, allowed_ops, x, y, _ctx))
+    {
+        return false;
+    }
+
+    // File contents are not allowed to be dragged into the page.
+    _drag_data = drag_data->Clone();
+    _drag_data->ResetFileContents();
+    return true;
+}
+
+void IRender::UpdateDragCursor(CefRefPtr<CefBrowser> browser, DragOperation operation)
+{
+    if (is_closed)
+    {
+        return;
+    }
+
+    _observer.on_update_drag_cursor(operation, _ctx);
+}
+
+void IRender::OnPopupSize(CefRefPtr<CefBrowser> browser, const CefRect& rect)
+{
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (rect.width <= 0 || rect.height <= 0)
+    {
+        return;
+    }
+
+    // Keep the popup inside the view.
+    _popup_rect = rect;
+    _popup_rect.x = std::max(std::min(_popup_rect.x, _width - _popup_rect.width), 0);
+    _popup_rect.y = std::max(std::min(_popup_rect.y, _height - _popup_rect.height), 0);
+
+    // Request a view frame to composite the popup into.
+    browser->GetHost()->Invalidate(PET_VIEW);
+
+    _observer.on_popup_change(true,
+                              Rect{ _popup_rect.x, _popup_rect.y, _popup_rect.width, _popup_rect.height },
+                              _ctx);
 }
 
 bool IRender::GetScreenInfo(CefRefPtr<CefBrowser> browser, CefScreenInfo& info)
@@ -105,6 +388,246 @@ void IRender::Resize(int width, int height)
     _browser.value()->GetHost()->WasResized();
 }
 
+void IRender::SetFrameRate(int frame_rate)
+{
+    // _settings is read by OnPaint and GetScreenInfo on the UI thread.
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI, base::BindOnce(&IRender::SetFrameRate, CefRefPtr<IRender>(this), frame_rate));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _settings.frame_rate = frame_rate;
+    _browser.value()->GetHost()->SetWindowlessFrameRate(frame_rate);
+}
+
+void IRender::SetHidden(bool hidden)
+{
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->WasHidden(hidden);
+}
+
+void IRender::Invalidate()
+{
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->Invalidate(PET_VIEW);
+}
+
+void IRender::SendBeginFrame(uint64_t id)
+{
+    // OnPaint reads the id on the UI thread.
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI, base::BindOnce(&IRender::SendBeginFrame, CefRefPtr<IRender>(this), id));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    if (!_settings.is_offscreen || !_settings.external_begin_frame)
+    {
+        return;
+    }
+
+    _begin_frame = id;
+    _browser.value()->GetHost()->SendExternalBeginFrame();
+}
+
+void IRender::SetDeviceScaleFactor(float device_scale_factor)
+{
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI,
+                    base::BindOnce(&IRender::SetDeviceScaleFactor, CefRefPtr<IRender>(this), device_scale_factor));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    // GetScreenInfo reports the new factor, the view size in view coordinates
+    // stays the same while the frames are painted at the new scale.
+    _settings.device_scale_factor = device_scale_factor;
+    _browser.value()->GetHost()->NotifyScreenInfoChanged();
+    _browser.value()->GetHost()->WasResized();
+}
+
+void IRender::DragTargetDragEnter(const DragData* data, int x, int y, uint32_t allowed_operations)
+{
+    // The data of the host is owned by the caller, convert it before leaving
+    // the thread of the caller.
+    DragTargetDragEnterWith(data != nullptr ? from_c(data) : nullptr, x, y, allowed_operations);
+}
+
+void IRender::DragTargetDragEnterWith(CefRefPtr<CefDragData> data, int x, int y, uint32_t allowed_operations)
+{
+    // _drag_data is set by StartDragging on the UI thread.
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI,
+                    base::BindOnce(&IRender::DragTargetDragEnterWith,
+                                   CefRefPtr<IRender>(this),
+                                   data,
+                                   x,
+                                   y,
+                                   allowed_operations));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    CefRefPtr<CefDragData> drag_data = data ? data : _drag_data;
+    if (!drag_data)
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->DragTargetDragEnter(drag_data,
+                                                     mouse_event(x, y),
+                                                     (DragOperationsMask)allowed_operations);
+}
+
+void IRender::DragTargetDragOver(int x, int y, uint32_t allowed_operations)
+{
+    // The drag target calls stay in order with DragTargetDragEnter.
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI,
+                    base::BindOnce(&IRender::DragTargetDragOver, CefRefPtr<IRender>(this), x, y, allowed_operations));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->DragTargetDragOver(mouse_event(x, y), (DragOperationsMask)allowed_operations);
+}
+
+void IRender::DragTargetDragLeave()
+{
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI, base::BindOnce(&IRender::DragTargetDragLeave, CefRefPtr<IRender>(this)));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->DragTargetDragLeave();
+}
+
+void IRender::DragTargetDrop(int x, int y)
+{
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI, base::BindOnce(&IRender::DragTargetDrop, CefRefPtr<IRender>(this), x, y));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _browser.value()->GetHost()->DragTargetDrop(mouse_event(x, y));
+}
+
+void IRender::DragSourceEnded(int x, int y, uint32_t operation)
+{
+    // _drag_data is set by StartDragging on the UI thread.
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI,
+                    base::BindOnce(&IRender::DragSourceEnded, CefRefPtr<IRender>(this), x, y, operation));
+        return;
+    }
+
+    if (is_closed)
+    {
+        return;
+    }
+
+    if (!_browser.has_value())
+    {
+        return;
+    }
+
+    _drag_data = nullptr;
+    _browser.value()->GetHost()->DragSourceEndedAt(x, y, (DragOperationsMask)operation);
+    _browser.value()->GetHost()->DragSourceSystemDragEnded();
+}
+
 void IRender::IClose()
 {
     _browser = std::nullopt;
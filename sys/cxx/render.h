@@ -10,6 +10,7 @@
 #pragma once
 
 #include <optional>
+#include <vector>
 
 #include "include/cef_app.h"
 #include "webview.h"
@@ -28,7 +29,7 @@ public:
     virtual bool GetScreenInfo(CefRefPtr<CefBrowser> browser, CefScreenInfo& screen_info) override;
     virtual void OnImeCompositionRangeChanged(CefRefPtr<CefBrowser> browser,
                                               const CefRange& selected_range,
-                                              const RectList& character_bounds);
+                                              const RectList& character_bounds) override;
     virtual void GetViewRect(CefRefPtr<CefBrowser> browser, CefRect& rect) override;
     virtual void OnPaint(CefRefPtr<CefBrowser> browser,
                          PaintElementType type,
@@ -36,9 +37,27 @@ public:
                          const void* buffer,
                          int width,
                          int height) override;
+    virtual void OnPopupShow(CefRefPtr<CefBrowser> browser, bool show) override;
+    virtual void OnPopupSize(CefRefPtr<CefBrowser> browser, const CefRect& rect) override;
+    virtual bool StartDragging(CefRefPtr<CefBrowser> browser,
+                               CefRefPtr<CefDragData> drag_data,
+                               DragOperationsMask allowed_ops,
+                               int x,
+                               int y) override;
+    virtual void UpdateDragCursor(CefRefPtr<CefBrowser> browser, DragOperation operation) override;
 
     void SetBrowser(CefRefPtr<CefBrowser> browser);
     void Resize(int width, int height);
+    void SetFrameRate(int frame_rate);
+    void SetHidden(bool hidden);
+    void Invalidate();
+    void SendBeginFrame(uint64_t id);
+    void SetDeviceScaleFactor(float device_scale_factor);
+    void DragTargetDragEnter(const DragData* data, int x, int y, uint32_t allowed_operations);
+    void DragTargetDragOver(int x, int y, uint32_t allowed_operations);
+    void DragTargetDragLeave();
+    void DragTargetDrop(int x, int y);
+    void DragSourceEnded(int x, int y, uint32_t operation);
     void IClose();
 
 private:
@@ -51,6 +70,29 @@ private:
     int _width;
     int _height;
 
+    // While a popup widget (e.g. a <select> dropdown) is visible, the last
+    // view frame is kept and the popup is composited over it.
+    bool _is_popup_visible = false;
+    CefRect _popup_rect;
+    std::vector<uint8_t> _view_buffer;
+    int _view_width = 0;
+    int _view_height = 0;
+    std::vector<uint8_t> _popup_buffer;
+    int _popup_width = 0;
+    int _popup_height = 0;
+    std::vector<uint8_t> _frame_buffer;
+
+    // The id of the last begin frame sent by the host, only accessed on the
+    // UI thread.
+    uint64_t _begin_frame = 0;
+
+    void PaintWithPopup(std::vector<Rect>& rects);
+    void DragTargetDragEnterWith(CefRefPtr<CefDragData> data, int x, int y, uint32_t allowed_operations);
+
+    // The data of the drag started by the page, used when the host continues
+    // the drag over the page. Only accessed on the UI thread.
+    CefRefPtr<CefDragData> _drag_data = nullptr;
+
     IMPLEMENT_REFCOUNTING(IRender);
 };
 
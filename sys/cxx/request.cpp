@@ -0,0 +1,260 @@
+//
+//  request.cpp
+//  webview
+//
+
+#include "request.h"
+
+#include "include/base/cef_callback.h"
+#include "include/wrapper/cef_closure_task.h"
+#include "include/wrapper/cef_helpers.h"
+
+static const char* proxy_mode_name(ProxyMode mode)
+{
+    switch (mode)
+    {
+    case ProxyMode::kProxyFixedServers:
+        return "fixed_servers";
+    case ProxyMode::kProxyPacScript:
+        return "pac_script";
+    case ProxyMode::kProxySystem:
+        return "system";
+    default:
+        return "direct";
+    }
+}
+
+// The certificate fields are owned by the caller and only valid during the
+// observer callback.
+typedef struct
+{
+    std::string subject;
+    std::string issuer;
+    std::vector<uint8_t> der;
+} CertificateData;
+
+static Certificate to_c(CefRefPtr<CefX509Certificate> certificate, CertificateData& data)
+{
+    data.subject = certificate->GetSubject()->GetDisplayName().ToString();
+    data.issuer = certificate->GetIssuer()->GetDisplayName().ToString();
+
+    CefRefPtr<CefBinaryValue> der = certificate->GetDEREncoded();
+    if (der != nullptr)
+    {
+        data.der.resize(der->GetSize());
+        der->GetData(data.der.data(), data.der.size(), 0);
+    }
+
+    Certificate output;
+    output.subject = data.subject.c_str();
+    output.issuer = data.issuer.c_str();
+    output.der = data.der.data();
+    output.der_size = data.der.size();
+    return output;
+}
+
+CefRefPtr<CefValue> ProxyToValue(const ProxyOptions* proxy)
+{
+    assert(proxy);
+
+    CefRefPtr<CefDictionaryValue> dict = CefDictionaryValue::Create();
+    dict->SetString("mode", proxy_mode_name(proxy->mode));
+
+    if (proxy->server != nullptr)
+    {
+        dict->SetString("server", proxy->server);
+    }
+
+    if (proxy->bypass_list != nullptr)
+    {
+        dict->SetString("bypass_list", proxy->bypass_list);
+    }
+
+    if (proxy->pac_url != nullptr)
+    {
+        dict->SetString("pac_url", proxy->pac_url);
+    }
+
+    CefRefPtr<CefValue> value = CefValue::Create();
+    value->SetDictionary(dict);
+    return value;
+}
+
+void SetProxyPreference(CefRefPtr<CefRequestContext> context, CefRefPtr<CefValue> proxy)
+{
+    if (!CefCurrentlyOn(TID_UI))
+    {
+        CefPostTask(TID_UI, base::BindOnce(&SetProxyPreference, context, proxy));
+        return;
+    }
+
+    CefString error;
+    if (!context->SetPreference("proxy", proxy, error))
+    {
+        LOG(ERROR) << "failed to set the proxy preference: " << error.ToString();
+    }
+}
+
+/* =================== IRequestContext ================= */
+
+IRequestContext::IRequestContext(CefRefPtr<CefValue> proxy) : _proxy(proxy)
+{
+}
+
+void IRequestContext::OnRequestContextInitialized(CefRefPtr<CefRequestContext> request_context)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    SetProxyPreference(request_context, _proxy);
+}
+
+/* =================== IRequest ================= */
+
+IRequest::IRequest(PageOptions settings, PageObserver observer, void* ctx)
+    : _settings(settings)
+    , _observer(observer)
+    , _ctx(ctx)
+{
+}
+
+bool IRequest::GetAuthCredentials(CefRefPtr<CefBrowser> browser,
+                                  const CefString& origin_url,
+                                  bool isProxy,
+                                  const CefString& host,
+                                  int port,
+                                  const CefString& realm,
+                                  const CefString& scheme,
+                                  CefRefPtr<CefAuthCallback> callback)
+{
+    CEF_REQUIRE_IO_THREAD();
+
+    if (_is_closed)
+    {
+        return false;
+    }
+
+    // The observer owns the callback until it continues or cancels it.
+    AuthCallback* auth = new AuthCallback;
+    auth->ref = callback;
+
+    _observer.on_auth_credentials(origin_url.ToString().c_str(),
+                                  host.ToString().c_str(),
+                                  port,
+                                  realm.ToString().c_str(),
+                                  scheme.ToString().c_str(),
+                                  isProxy,
+                                  auth,
+                                  _ctx);
+    return true;
+}
+
+bool IRequest::OnCertificateError(CefRefPtr<CefBrowser> browser,
+                                  cef_errorcode_t cert_error,
+                                  const CefString& request_url,
+                                  CefRefPtr<CefSSLInfo> ssl_info,
+                                  CefRefPtr<CefCallback> callback)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return false;
+    }
+
+    CertificateData data;
+    std::optional<Certificate> certificate = std::nullopt;
+    if (ssl_info != nullptr && ssl_info->GetX509Certificate() != nullptr)
+    {
+        certificate = to_c(ssl_info->GetX509Certificate(), data);
+    }
+
+    // The observer owns the callback until it continues or cancels it.
+    CertificateErrorCallback* certificate_error = new CertificateErrorCallback;
+    certificate_error->ref = callback;
+
+    _observer.on_certificate_error(request_url.ToString().c_str(),
+                                   cert_error,
+                                   certificate.has_value() ? &certificate.value() : nullptr,
+                                   certificate_error,
+                                   _ctx);
+    return true;
+}
+
+bool IRequest::OnSelectClientCertificate(CefRefPtr<CefBrowser> browser,
+                                         bool isProxy,
+                                         const CefString& host,
+                                         int port,
+                                         const X509CertificateList& certificates,
+                                         CefRefPtr<CefSelectClientCertificateCallback> callback)
+{
+    CEF_REQUIRE_UI_THREAD();
+
+    if (_is_closed)
+    {
+        return false;
+    }
+
+    std::vector<CertificateData> data(certificates.size());
+    std::vector<Certificate> list;
+    for (size_t i = 0; i < certificates.size(); i++)
+    {
+        list.push_back(to_c(certificates[i], data[i]));
+    }
+
+    // The observer owns the callback until it selects a certificate.
+    SelectClientCertificateCallback* select = new SelectClientCertificateCallback;
+    select->ref = callback;
+    select->certificates = certificates;
+
+    _observer.on_select_client_certificate(host.ToString().c_str(),
+                                           port,
+                                           isProxy,
+                                           list.data(),
+                                           list.size(),
+                                           select,
+                                           _ctx);
+    return true;
+}
+
+CefRefPtr<CefResourceRequestHandler> IRequest::GetResourceRequestHandler(CefRefPtr<CefBrowser> browser,
+                                                                         CefRefPtr<CefFrame> frame,
+                                                                         CefRefPtr<CefRequest> request,
+                                                                         bool is_navigation,
+                                                                         bool is_download,
+                                                                         const CefString& request_initiator,
+                                                                         bool& disable_default_handling)
+{
+    CEF_REQUIRE_IO_THREAD();
+
+    if (_is_closed)
+    {
+        return nullptr;
+    }
+
+    return this;
+}
+
+CefResourceRequestHandler::ReturnValue IRequest::OnBeforeResourceLoad(CefRefPtr<CefBrowser> browser,
+                                                                      CefRefPtr<CefFrame> frame,
+                                                                      CefRefPtr<CefRequest> request,
+                                                                      CefRefPtr<CefCallback> callback)
+{
+    CEF_REQUIRE_IO_THREAD();
+
+    if (_is_closed)
+    {
+        return RV_CONTINUE;
+    }
+
+    // ResourceType have the same value with cef_resource_type_t.
+    bool is_blocked = _observer.on_before_resource_load(request->GetURL().ToString().c_str(),
+                                                        request->GetFirstPartyForCookies().ToString().c_str(),
+                                                        (ResourceType)request->GetResourceType(),
+                                                        _ctx);
+    return is_blocked ? RV_CANCEL : RV_CONTINUE;
+}
+
+void IRequest::IClose()
+{
+    _is_closed = true;
+}
@@ -0,0 +1,114 @@
+//
+//  request.h
+//  webview
+//
+
+#ifndef LIBWEBVIEW_REQUEST_H
+#define LIBWEBVIEW_REQUEST_H
+#pragma once
+
+#include <optional>
+#include <vector>
+
+#include "include/cef_app.h"
+#include "webview.h"
+
+typedef struct
+{
+    CefRefPtr<CefAuthCallback> ref;
+} AuthCallback;
+
+typedef struct
+{
+    CefRefPtr<CefCallback> ref;
+} CertificateErrorCallback;
+
+typedef struct
+{
+    CefRefPtr<CefSelectClientCertificateCallback> ref;
+    CefRequestHandler::X509CertificateList certificates;
+} SelectClientCertificateCallback;
+
+//
+// Convert the proxy options to the value of the chromium "proxy" preference.
+//
+CefRefPtr<CefValue> ProxyToValue(const ProxyOptions* proxy);
+
+//
+// Set the "proxy" preference of the request context, this is always done on
+// the browser process UI thread.
+//
+void SetProxyPreference(CefRefPtr<CefRequestContext> context, CefRefPtr<CefValue> proxy);
+
+class IRequestContext : public CefRequestContextHandler
+{
+public:
+    IRequestContext(CefRefPtr<CefValue> proxy);
+
+    /* CefRequestContextHandler */
+
+    virtual void OnRequestContextInitialized(CefRefPtr<CefRequestContext> request_context) override;
+
+private:
+    CefRefPtr<CefValue> _proxy;
+
+    IMPLEMENT_REFCOUNTING(IRequestContext);
+};
+
+class IRequest : public CefRequestHandler, public CefResourceRequestHandler
+{
+public:
+    IRequest(PageOptions settings, PageObserver observer, void* ctx);
+    ~IRequest()
+    {
+        IClose();
+    }
+
+    /* CefRequestHandler */
+
+    virtual bool GetAuthCredentials(CefRefPtr<CefBrowser> browser,
+                                    const CefString& origin_url,
+                                    bool isProxy,
+                                    const CefString& host,
+                                    int port,
+                                    const CefString& realm,
+                                    const CefString& scheme,
+                                    CefRefPtr<CefAuthCallback> callback) override;
+    virtual bool OnCertificateError(CefRefPtr<CefBrowser> browser,
+                                    cef_errorcode_t cert_error,
+                                    const CefString& request_url,
+                                    CefRefPtr<CefSSLInfo> ssl_info,
+                                    CefRefPtr<CefCallback> callback) override;
+    virtual bool OnSelectClientCertificate(CefRefPtr<CefBrowser> browser,
+                                           bool isProxy,
+                                           const CefString& host,
+                                           int port,
+                                           const X509CertificateList& certificates,
+                                           CefRefPtr<CefSelectClientCertificateCallback> callback) override;
+    virtual CefRefPtr<CefResourceRequestHandler> GetResourceRequestHandler(CefRefPtr<CefBrowser> browser,
+                                                                           CefRefPtr<CefFrame> frame,
+                                                                           CefRefPtr<CefRequest> request,
+                                                                           bool is_navigation,
+                                                                           bool is_download,
+                                                                           const CefString& request_initiator,
+                                                                           bool& disable_default_handling) override;
+
+    /* CefResourceRequestHandler */
+
+    virtual ReturnValue OnBeforeResourceLoad(CefRefPtr<CefBrowser> browser,
+                                             CefRefPtr<CefFrame> frame,
+                                             CefRefPtr<CefRequest> request,
+                                             CefRefPtr<CefCallback> callback) override;
+
+    void IClose();
+
+private:
+    bool _is_closed = false;
+    PageOptions _settings;
+    PageObserver _observer;
+    void* _ctx;
+
+    IMPLEMENT_REFCOUNTING(IRequest);
+};
+
+#endif  // LIBWEBVIEW_REQUEST_H
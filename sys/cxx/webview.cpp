@@ -73,6 +73,16 @@ void webview_exit(void* app_ptr)
     delete app;
 }
 
+void webview_set_proxy(void* app_ptr, const ProxyOptions* proxy)
+{
+    assert(app_ptr);
+    assert(proxy);
+
+    auto app = (App*)app_ptr;
+
+    app->ref->SetProxy(proxy);
+}
+
 void* create_page(void* app_ptr,
                   const char* url,
                   const PageOptions* settings,
@@ -99,69 +109,84 @@ void page_exit(void* browser)
     delete page;
 }
 
-void page_send_mouse_click(void* browser, MouseButtons button, bool pressed)
+void page_send_mouse_click(void* browser,
+                           MouseButtons button,
+                           bool pressed,
+                           int click_count,
+                           uint32_t modifiers)
 {
     assert(browser);
 
     auto page = (Browser*)browser;
 
-    page->ref->OnMouseClick(button, pressed);
+    page->ref->OnMouseClick(button, pressed, click_count, modifiers);
 }
 
 void page_send_mouse_click_with_pos(void* browser,
                                     MouseButtons button,
                                     bool pressed,
                                     int x,
-                                    int y)
+                                    int y,
+                                    int click_count,
+                                    uint32_t modifiers)
 {
     assert(browser);
 
     auto page = (Browser*)browser;
 
-    page->ref->OnMouseClickWithPosition(button, x, y, pressed);
+    page->ref->OnMouseClickWithPosition(button, x, y, pressed, click_count, modifiers);
 }
 
-void page_send_mouse_wheel(void* browser, int x, int y)
+void page_send_mouse_wheel(void* browser,
+                           int x,
+                           int y,
+                           int delta_x,
+                           int delta_y,
+                           uint32_t modifiers)
 {
     assert(browser);
 
     auto page = (Browser*)browser;
 
-    page->ref->OnMouseWheel(x, y);
+    page->ref->OnMouseWheel(x, y, delta_x, delta_y, modifiers);
 }
 
-void page_send_mouse_move(void* browser, int x, int y)
+void page_send_mouse_move(void* browser, int x, int y, uint32_t modifiers)
 {
     assert(browser);
 
     auto page = (Browser*)browser;
 
-    page->ref->OnMouseMove(x, y);
+    page->ref->OnMouseMove(x, y, modifiers);
 }
 
-void page_send_keyboard(void* browser, int scan_code, bool pressed, Modifiers modifiers)
+void page_send_mouse_leave(void* browser, uint32_t modifiers)
 {
     assert(browser);
 
     auto page = (Browser*)browser;
 
-    page->ref->OnKeyboard(scan_code, pressed, modifiers);
+    page->ref->OnMouseLeave(modifiers);
 }
 
-void page_send_touch(void* browser,
-                     int id,
-                     int x,
-                     int y,
-                     TouchEventType type,
-                     TouchPointerType pointer_type)
+void page_send_key_event(void* browser, const KeyEvent* event)
 {
     assert(browser);
+    assert(event);
 
     auto page = (Browser*)browser;
 
-    // TouchEventType have the same value with cef_touch_event_type_t.
-    // Same as TouchPointerType.
-    page->ref->OnTouch(id, x, y, (cef_touch_event_type_t)type, (cef_pointer_type_t)pointer_type);
+    page->ref->OnKeyEvent(event);
+}
+
+void page_send_touch(void* browser, const TouchEvent* event)
+{
+    assert(browser);
+    assert(event);
+
+    auto page = (Browser*)browser;
+
+    page->ref->OnTouch(event);
 }
 
 void page_send_message(void* browser, const char* message)
@@ -192,6 +217,135 @@ void page_resize(void* browser, int width, int height)
     page->ref->Resize(width, height);
 }
 
+void page_set_focus(void* browser, bool focus)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->SetFocus(focus);
+}
+
+void page_set_frame_rate(void* browser, int frame_rate)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->SetFrameRate(frame_rate);
+}
+
+void page_set_hidden(void* browser, bool hidden)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->SetHidden(hidden);
+}
+
+void page_invalidate(void* browser)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->Invalidate();
+}
+
+void page_send_begin_frame(void* browser, uint64_t id)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->SendBeginFrame(id);
+}
+
+void page_notify_screen_info_changed(void* browser, float device_scale_factor)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->SetDeviceScaleFactor(device_scale_factor);
+}
+
+void page_get_content_size(void* browser, uint32_t timeout, ContentSizeCallback callback, void* ctx)
+{
+    assert(browser);
+    assert(callback);
+
+    auto page = (Browser*)browser;
+
+    page->ref->GetContentSize(timeout, callback, ctx);
+}
+
+void page_drag_target_drag_enter(void* browser,
+                                 const DragData* data,
+                                 int x,
+                                 int y,
+                                 uint32_t allowed_operations)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->DragTargetDragEnter(data, x, y, allowed_operations);
+}
+
+void page_drag_target_drag_over(void* browser, int x, int y, uint32_t allowed_operations)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->DragTargetDragOver(x, y, allowed_operations);
+}
+
+void page_drag_target_drag_leave(void* browser)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->DragTargetDragLeave();
+}
+
+void page_drag_target_drop(void* browser, int x, int y)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->DragTargetDrop(x, y);
+}
+
+void page_drag_source_ended(void* browser, int x, int y, uint32_t operation)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->DragSourceEnded(x, y, operation);
+}
+
+void page_print_to_pdf(void* browser,
+                       const char* path,
+                       const PdfOptions* options,
+                       PrintToPdfCallback callback,
+                       void* ctx)
+{
+    assert(browser);
+    assert(path);
+    assert(options);
+    assert(callback);
+
+    auto page = (Browser*)browser;
+
+    page->ref->PrintToPdf(std::string(path), options, callback, ctx);
+}
+
 const void* page_get_hwnd(void* browser)
 {
     assert(browser);
@@ -202,22 +356,174 @@ const void* page_get_hwnd(void* browser)
     return (void*)hwnd;
 }
 
-void page_send_ime_composition(void* browser, const char* input)
+void page_send_ime_set_composition(void* browser,
+                                   const char* text,
+                                   const ImeUnderline* underlines,
+                                   size_t underlines_size,
+                                   const ImeRange* replacement_range,
+                                   const ImeRange* selection_range)
 {
     assert(browser);
-    assert(input);
+    assert(text);
 
     auto page = (Browser*)browser;
 
-    page->ref->OnIMEComposition(std::string(input));
+    page->ref->OnIMESetComposition(std::string(text),
+                                   underlines,
+                                   underlines_size,
+                                   replacement_range,
+                                   selection_range);
 }
 
-void page_send_ime_set_composition(void* browser, const char* input, int x, int y)
+void page_send_ime_commit_text(void* browser,
+                               const char* text,
+                               const ImeRange* replacement_range,
+                               int relative_cursor_pos)
 {
     assert(browser);
-    assert(input);
+    assert(text);
 
     auto page = (Browser*)browser;
 
-    page->ref->OnIMESetComposition(std::string(input), x, y);
+    page->ref->OnIMECommitText(std::string(text), replacement_range, relative_cursor_pos);
+}
+
+void page_send_ime_finish_composing_text(void* browser, bool keep_selection)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->OnIMEFinishComposingText(keep_selection);
+}
+
+void page_send_ime_cancel_composition(void* browser)
+{
+    assert(browser);
+
+    auto page = (Browser*)browser;
+
+    page->ref->OnIMECancelComposition();
+}
+
+void page_set_proxy(void* browser, const ProxyOptions* proxy)
+{
+    assert(browser);
+    assert(proxy);
+
+    auto page = (Browser*)browser;
+
+    page->ref->SetProxy(proxy);
+}
+
+void auth_callback_continue(void* callback, const char* username, const char* password)
+{
+    assert(callback);
+    assert(username);
+    assert(password);
+
+    auto auth = (AuthCallback*)callback;
+
+    auth->ref->Continue(std::string(username), std::string(password));
+    delete auth;
+}
+
+void auth_callback_cancel(void* callback)
+{
+    assert(callback);
+
+    auto auth = (AuthCallback*)callback;
+
+    auth->ref->Cancel();
+    delete auth;
+}
+
+void before_download_callback_continue(void* callback, const char* path, bool show_dialog)
+{
+    assert(callback);
+
+    auto before_download = (BeforeDownloadCallback*)callback;
+
+    before_download->ref->Continue(path != nullptr ? std::string(path) : std::string(), show_dialog);
+    delete before_download;
+}
+
+void before_download_callback_cancel(void* callback)
+{
+    assert(callback);
+
+    // Releasing the callback without continuing it cancels the download.
+    delete (BeforeDownloadCallback*)callback;
+}
+
+void download_callback_cancel(void* callback)
+{
+    assert(callback);
+
+    auto download = (DownloadCallback*)callback;
+
+    download->ref->Cancel();
+}
+
+void download_callback_pause(void* callback)
+{
+    assert(callback);
+
+    auto download = (DownloadCallback*)callback;
+
+    download->ref->Pause();
+}
+
+void download_callback_resume(void* callback)
+{
+    assert(callback);
+
+    auto download = (DownloadCallback*)callback;
+
+    download->ref->Resume();
+}
+
+void download_callback_release(void* callback)
+{
+    assert(callback);
+
+    delete (DownloadCallback*)callback;
+}
+
+void certificate_error_callback_continue(void* callback)
+{
+    assert(callback);
+
+    auto certificate_error = (CertificateErrorCallback*)callback;
+
+    certificate_error->ref->Continue();
+    delete certificate_error;
+}
+
+void certificate_error_callback_cancel(void* callback)
+{
+    assert(callback);
+
+    auto certificate_error = (CertificateErrorCallback*)callback;
+
+    certificate_error->ref->Cancel();
+    delete certificate_error;
+}
+
+void select_client_certificate_callback_select(void* callback, int index)
+{
+    assert(callback);
+
+    auto select = (SelectClientCertificateCallback*)callback;
+
+    if (index >= 0 && index < (int)select->certificates.size())
+    {
+        select->ref->Select(select->certificates[index]);
+    }
+    else
+    {
+        select->ref->Select(nullptr);
+    }
+
+    delete select;
 }
@@ -15,14 +15,34 @@
 #define EXPORT
 #endif
 
+#include <stddef.h>
 #include <stdint.h>
 #include <stdbool.h>
 
+typedef enum
+{
+    kProxyDirect = 0,
+    kProxyFixedServers = 1,
+    kProxyPacScript = 2,
+    kProxySystem = 3,
+} ProxyMode;
+
+typedef struct
+{
+    ProxyMode mode;
+    const char* server;
+    const char* bypass_list;
+    const char* pac_url;
+} ProxyOptions;
+
 typedef struct
 {
     const char* cache_path;
     const char* browser_subprocess_path;
     const char* scheme_path;
+    const ProxyOptions* proxy;
+    // ARGB, the alpha must be either fully opaque or fully transparent.
+    uint32_t background_color;
 } WebviewOptions;
 
 typedef struct
@@ -33,6 +53,13 @@ typedef struct
     uint32_t height;
     float device_scale_factor;
     bool is_offscreen;
+    const ProxyOptions* proxy;
+    // ARGB, a fully transparent color enables transparent painting for
+    // offscreen pages and uses the webview background color for windowed pages.
+    uint32_t background_color;
+    // Offscreen pages only paint when page_send_begin_frame is called instead
+    // of on the internal timer of frame_rate.
+    bool external_begin_frame;
 } PageOptions;
 
 typedef enum
@@ -40,16 +67,40 @@ typedef enum
     kLeft,
     kRight,
     kMiddle,
+    // The back and forward buttons navigate the history on release.
+    kBack,
+    kForward,
 } MouseButtons;
 
 typedef enum
 {
-    kNone = 0,
-    kShift = 1,
-    kCtrl = 2,
-    kAlt = 3,
-    kWin = 4,
-} Modifiers;
+    kKeyRawDown = 0,
+    kKeyDown = 1,
+    kKeyUp = 2,
+    kKeyChar = 3,
+} KeyEventType;
+
+typedef struct
+{
+    // KeyEventType have the same value with cef_key_event_type_t.
+    KeyEventType type;
+    // Bit flags of cef_event_flags_t, see the modifiers of page_send_mouse_click.
+    uint32_t modifiers;
+    // The Windows virtual key code of the key, or the character for
+    // kKeyChar.
+    int windows_key_code;
+    // The lParam of the key message on Windows, the xkb keycode on Linux and
+    // the key code on macOS.
+    int native_key_code;
+    // Indicates whether the event is considered a "system key" event, e.g.
+    // the key is pressed with alt on Windows.
+    bool is_system_key;
+    // The character generated by the keystroke as a UTF-16 code unit.
+    uint16_t character;
+    // Same as |character| but unmodified by any concurrently-held modifiers
+    // (except shift).
+    uint16_t unmodified_character;
+} KeyEvent;
 
 typedef enum
 {
@@ -68,6 +119,31 @@ typedef enum
     kUnknown = 4,
 } TouchPointerType;
 
+typedef struct
+{
+    // Id of a touch point. Must be unique per touch, can be any number except
+    // -1. Note that a maximum of 16 concurrent touches will be tracked; touches
+    // beyond that will be ignored.
+    int id;
+    // X coordinate relative to the left side of the view.
+    float x;
+    // Y coordinate relative to the top side of the view.
+    float y;
+    // X radius in pixels. Set to 0 if not applicable.
+    float radius_x;
+    // Y radius in pixels. Set to 0 if not applicable.
+    float radius_y;
+    // Rotation angle in radians. Set to 0 if not applicable.
+    float rotation_angle;
+    // The normalized pressure of the pointer input in the range of [0,1].
+    // Set to 0 if not applicable.
+    float pressure;
+    TouchEventType type;
+    // Bit flags of cef_event_flags_t, see the modifiers of page_send_mouse_click.
+    uint32_t modifiers;
+    TouchPointerType pointer_type;
+} TouchEvent;
+
 typedef enum
 {
     Load = 1,
@@ -85,16 +161,294 @@ typedef struct
     int height;
 } Rect;
 
+typedef enum
+{
+    kDownloadInProgress = 0,
+    kDownloadComplete = 1,
+    kDownloadCanceled = 2,
+    kDownloadInterrupted = 3,
+} DownloadState;
+
+typedef struct
+{
+    uint32_t id;
+    const char* url;
+    const char* original_url;
+    const char* suggested_file_name;
+    const char* full_path;
+    const char* mime_type;
+    int64_t received_bytes;
+    int64_t total_bytes;
+    int64_t current_speed;
+    int percent_complete;
+    DownloadState state;
+} DownloadItem;
+
+typedef enum
+{
+    kResourceMainFrame = 0,
+    kResourceSubFrame = 1,
+    kResourceStylesheet = 2,
+    kResourceScript = 3,
+    kResourceImage = 4,
+    kResourceFont = 5,
+    kResourceSubResource = 6,
+    kResourceObject = 7,
+    kResourceMedia = 8,
+    kResourceWorker = 9,
+    kResourceSharedWorker = 10,
+    kResourcePrefetch = 11,
+    kResourceFavicon = 12,
+    kResourceXhr = 13,
+    kResourcePing = 14,
+    kResourceServiceWorker = 15,
+    kResourceCspReport = 16,
+    kResourcePluginResource = 17,
+    kResourceNavigationPreloadMainFrame = 19,
+    kResourceNavigationPreloadSubFrame = 20,
+} ResourceType;
+
+typedef struct
+{
+    const char* subject;
+    const char* issuer;
+    const uint8_t* der;
+    size_t der_size;
+} Certificate;
+
+typedef enum
+{
+    kCursorPointer = 0,
+    kCursorCross = 1,
+    kCursorHand = 2,
+    kCursorIBeam = 3,
+    kCursorWait = 4,
+    kCursorHelp = 5,
+    kCursorEastResize = 6,
+    kCursorNorthResize = 7,
+    kCursorNorthEastResize = 8,
+    kCursorNorthWestResize = 9,
+    kCursorSouthResize = 10,
+    kCursorSouthEastResize = 11,
+    kCursorSouthWestResize = 12,
+    kCursorWestResize = 13,
+    kCursorNorthSouthResize = 14,
+    kCursorEastWestResize = 15,
+    kCursorNorthEastSouthWestResize = 16,
+    kCursorNorthWestSouthEastResize = 17,
+    kCursorColumnResize = 18,
+    kCursorRowResize = 19,
+    kCursorMiddlePanning = 20,
+    kCursorEastPanning = 21,
+    kCursorNorthPanning = 22,
+    kCursorNorthEastPanning = 23,
+    kCursorNorthWestPanning = 24,
+    kCursorSouthPanning = 25,
+    kCursorSouthEastPanning = 26,
+    kCursorSouthWestPanning = 27,
+    kCursorWestPanning = 28,
+    kCursorMove = 29,
+    kCursorVerticalText = 30,
+    kCursorCell = 31,
+    kCursorContextMenu = 32,
+    kCursorAlias = 33,
+    kCursorProgress = 34,
+    kCursorNoDrop = 35,
+    kCursorCopy = 36,
+    kCursorNone = 37,
+    kCursorNotAllowed = 38,
+    kCursorZoomIn = 39,
+    kCursorZoomOut = 40,
+    kCursorGrab = 41,
+    kCursorGrabbing = 42,
+    kCursorMiddlePanningVertical = 43,
+    kCursorMiddlePanningHorizontal = 44,
+    kCursorCustom = 45,
+    kCursorDndNone = 46,
+    kCursorDndMove = 47,
+    kCursorDndCopy = 48,
+    kCursorDndLink = 49,
+} CursorType;
+
+typedef struct
+{
+    int hotspot_x;
+    int hotspot_y;
+    float image_scale_factor;
+    // BGRA pixels of width * height * 4 bytes.
+    const void* buffer;
+    int width;
+    int height;
+} CursorInfo;
+
+// Drag operations are a mask of cef_drag_operations_mask_t values, e.g. copy
+// is 1, link is 2 and move is 16.
+typedef struct
+{
+    const char* link_url;
+    const char* link_title;
+    const char* fragment_text;
+    const char* fragment_html;
+    const char* fragment_base_url;
+    const char** file_paths;
+    size_t file_paths_size;
+} DragData;
+
+typedef struct
+{
+    int hotspot_x;
+    int hotspot_y;
+    // BGRA pixels of width * height * 4 bytes.
+    const void* buffer;
+    int width;
+    int height;
+} DragImage;
+
 typedef void (*CreateWebviewCallback)(void* ctx);
 
+typedef void (*ContentSizeCallback)(bool success, int width, int height, void* ctx);
+
+typedef enum
+{
+    kPdfMarginDefault = 0,
+    kPdfMarginNone = 1,
+    kPdfMarginCustom = 2,
+} PdfMarginType;
+
+typedef struct
+{
+    bool landscape;
+    bool print_background;
+    // The scale of the page rendering, 0 for the default of 1.
+    double scale;
+    // The paper size in inches, 0 for the default of US Letter (8.5 x 11).
+    double paper_width;
+    double paper_height;
+    bool prefer_css_page_size;
+    PdfMarginType margin_type;
+    // The margins in inches, only used with kPdfMarginCustom.
+    double margin_top;
+    double margin_right;
+    double margin_bottom;
+    double margin_left;
+    // Paper ranges to print, e.g. "1-5, 8, 11-13", all pages if null.
+    const char* page_ranges;
+    bool display_header_footer;
+    const char* header_template;
+    const char* footer_template;
+} PdfOptions;
+
+typedef void (*PrintToPdfCallback)(bool success, void* ctx);
+
+typedef enum
+{
+    // The source is explicit navigation via the API (LoadURL(), etc).
+    kFocusSourceNavigation = 0,
+    // The source is a system-generated focus event.
+    kFocusSourceSystem = 1,
+} FocusSource;
+
+typedef enum
+{
+    // Send the event to the page.
+    kPreKeyEventContinue = 0,
+    // Send the event to the page as a keyboard shortcut, on_key_event is
+    // called if the page doesn't handle it.
+    kPreKeyEventShortcut = 1,
+    // The host handled the event, the page never sees it.
+    kPreKeyEventConsume = 2,
+} PreKeyEventResult;
+
+//
+// A range of characters in UTF-16 code units, |to| is exclusive.
+//
+typedef struct
+{
+    uint32_t from;
+    uint32_t to;
+} ImeRange;
+
+typedef enum
+{
+    kImeUnderlineSolid = 0,
+    kImeUnderlineDot = 1,
+    kImeUnderlineDash = 2,
+    kImeUnderlineNone = 3,
+} ImeUnderlineStyle;
+
+typedef struct
+{
+    // The range of the underline in the composition text.
+    ImeRange range;
+    // ARGB, the color of the underline.
+    uint32_t color;
+    // ARGB, the background color of the range.
+    uint32_t background_color;
+    bool thick;
+    ImeUnderlineStyle style;
+} ImeUnderline;
+
 typedef struct
 {
     void (*on_state_change)(PageState state, void* ctx);
-    void (*on_ime_rect)(Rect rect, void* ctx);
-    void (*on_frame)(const void* buf, int width, int height, void* ctx);
+    void (*on_ime_composition_range_changed)(ImeRange selected_range,
+                                             const Rect* character_bounds,
+                                             size_t character_bounds_size,
+                                             void* ctx);
+    void (*on_popup_change)(bool visible, Rect rect, void* ctx);
+    void (*on_frame)(const void* buf,
+                     int width,
+                     int height,
+                     int stride,
+                     float device_scale_factor,
+                     const Rect* dirty_rects,
+                     size_t dirty_rects_size,
+                     // The id of the last page_send_begin_frame before the
+                     // paint, 0 if none.
+                     uint64_t begin_frame,
+                     void* ctx);
     void (*on_title_change)(const char* title, void* ctx);
     void (*on_fullscreen_change)(bool fullscreen, void* ctx);
+    void (*on_cursor_change)(CursorType type, const CursorInfo* custom_cursor, void* ctx);
+    bool (*on_start_dragging)(const DragData* data,
+                              const DragImage* image,
+                              uint32_t allowed_operations,
+                              int x,
+                              int y,
+                              void* ctx);
+    void (*on_update_drag_cursor)(uint32_t operation, void* ctx);
+    void (*on_take_focus)(bool next, void* ctx);
+    bool (*on_set_focus)(FocusSource source, void* ctx);
+    void (*on_got_focus)(void* ctx);
+    PreKeyEventResult (*on_pre_key_event)(const KeyEvent* event, void* ctx);
+    bool (*on_key_event)(const KeyEvent* event, void* ctx);
     void (*on_message)(const char* message, void* ctx);
+    void (*on_auth_credentials)(const char* origin_url,
+                                const char* host,
+                                int port,
+                                const char* realm,
+                                const char* scheme,
+                                bool is_proxy,
+                                void* callback,
+                                void* ctx);
+    void (*on_before_download)(const DownloadItem* item, void* callback, void* ctx);
+    void (*on_download_updated)(const DownloadItem* item, void* callback, void* ctx);
+    void (*on_certificate_error)(const char* url,
+                                 int error_code,
+                                 const Certificate* certificate,
+                                 void* callback,
+                                 void* ctx);
+    void (*on_select_client_certificate)(const char* host,
+                                         int port,
+                                         bool is_proxy,
+                                         const Certificate* certificates,
+                                         size_t certificates_size,
+                                         void* callback,
+                                         void* ctx);
+    bool (*on_before_resource_load)(const char* url,
+                                    const char* first_party_url,
+                                    ResourceType type,
+                                    void* ctx);
 } PageObserver;
 
 #ifdef __cplusplus
@@ -119,6 +473,12 @@ extern "C" {
     //
     EXPORT void webview_exit(void* app);
 
+    //
+    // Set the proxy of the global request context, the change takes effect
+    // without restarting.
+    //
+    EXPORT void webview_set_proxy(void* app, const ProxyOptions* proxy);
+
     EXPORT void* create_page(void* app,
                              const char* url,
                              const PageOptions* settings,
@@ -128,11 +488,18 @@ extern "C" {
     EXPORT void page_exit(void* browser);
 
     //
-    // Send a mouse click event to the browser.
+    // Send a mouse click event to the browser. |click_count| is 1 for a single
+    // click, 2 for the second press of a double click and so on.
+    //
+    // The |modifiers| of the input events are bit flags of cef_event_flags_t,
+    // e.g. EVENTFLAG_SHIFT_DOWN | EVENTFLAG_CONTROL_DOWN. The flags of the mouse
+    // buttons held down are tracked by the page and added to mouse events.
     //
     EXPORT void page_send_mouse_click(void* browser,
                                       MouseButtons button,
-                                      bool pressed);
+                                      bool pressed,
+                                      int click_count,
+                                      uint32_t modifiers);
 
     //
     // Send a mouse click event to the browser. The |x| and |y| coordinates are
@@ -142,40 +509,46 @@ extern "C" {
                                                MouseButtons button,
                                                bool pressed,
                                                int x,
-                                               int y);
+                                               int y,
+                                               int click_count,
+                                               uint32_t modifiers);
 
     //
     // Send a mouse wheel event to the browser. The |x| and |y| coordinates are
-    // relative to the upper-left corner of the view. The |deltaX| and |deltaY|
-    // values represent the movement delta in the X and Y directions
+    // relative to the upper-left corner of the view. The |delta_x| and |delta_y|
+    // values represent the movement delta in pixels in the X and Y directions
     // respectively. In order to scroll inside select popups with window
     // rendering disabled CefRenderHandler::GetScreenPoint should be implemented
     // properly.
     //
-    EXPORT void page_send_mouse_wheel(void* browser, int x, int y);
+    EXPORT void page_send_mouse_wheel(void* browser,
+                                      int x,
+                                      int y,
+                                      int delta_x,
+                                      int delta_y,
+                                      uint32_t modifiers);
 
     //
     // Send a mouse move event to the browser. The |x| and |y| coordinates are
     // relative to the upper-left corner of the view.
     //
-    EXPORT void page_send_mouse_move(void* browser, int x, int y);
+    EXPORT void page_send_mouse_move(void* browser, int x, int y, uint32_t modifiers);
+
+    //
+    // Send a mouse leave event to the browser, e.g. when the mouse leaves the
+    // window showing the view.
+    //
+    EXPORT void page_send_mouse_leave(void* browser, uint32_t modifiers);
 
     //
     // Send a key event to the browser.
     //
-    EXPORT void page_send_keyboard(void* browser,
-                                   int scan_code,
-                                   bool pressed,
-                                   Modifiers modifiers);
+    EXPORT void page_send_key_event(void* browser, const KeyEvent* event);
+
     //
     // Send a touch event to the browser.
     //
-    EXPORT void page_send_touch(void* browser,
-                                int id,
-                                int x,
-                                int y,
-                                TouchEventType type,
-                                TouchPointerType pointer_type);
+    EXPORT void page_send_touch(void* browser, const TouchEvent* event);
 
     EXPORT void page_send_message(void* browser, const char* message);
 
@@ -183,11 +556,189 @@ extern "C" {
 
     EXPORT void page_resize(void* browser, int width, int height);
 
+    //
+    // Set whether the browser is focused, e.g. when the window showing an
+    // offscreen page gains or loses focus.
+    //
+    EXPORT void page_set_focus(void* browser, bool focus);
+
+    //
+    // Set the maximum rate in frames per second (fps) that OnPaint will be called.
+    //
+    EXPORT void page_set_frame_rate(void* browser, int frame_rate);
+
+    //
+    // Notify the browser that it has been hidden or shown, painting stops while
+    // the page is hidden.
+    //
+    EXPORT void page_set_hidden(void* browser, bool hidden);
+
+    //
+    // Invalidate the view, the browser will call OnPaint asynchronously.
+    //
+    EXPORT void page_invalidate(void* browser);
+
+    //
+    // Issue a BeginFrame request to Chromium, only used when external_begin_frame
+    // is enabled. |id| is passed to the on_frame calls that follow it.
+    //
+    EXPORT void page_send_begin_frame(void* browser, uint64_t id);
+
+    //
+    // Change the device scale factor and notify the browser that the screen info
+    // has changed.
+    //
+    EXPORT void page_notify_screen_info_changed(void* browser, float device_scale_factor);
+
+    //
+    // Get the size of the page content in view coordinates, the callback is
+    // called exactly once with success set to false if the size is unavailable
+    // or not received within |timeout| milliseconds.
+    //
+    EXPORT void page_get_content_size(void* browser, uint32_t timeout, ContentSizeCallback callback, void* ctx);
+
+    //
+    // Call when the user drags the mouse into the page. The |x| and |y|
+    // coordinates are relative to the upper-left corner of the view. Set |data|
+    // to null to continue the drag started by the page itself.
+    //
+    EXPORT void page_drag_target_drag_enter(void* browser,
+                                            const DragData* data,
+                                            int x,
+                                            int y,
+                                            uint32_t allowed_operations);
+
+    //
+    // Call each time the mouse is moved across the page during a drag operation.
+    //
+    EXPORT void page_drag_target_drag_over(void* browser, int x, int y, uint32_t allowed_operations);
+
+    //
+    // Call when the user drags the mouse out of the page.
+    //
+    EXPORT void page_drag_target_drag_leave(void* browser);
+
+    //
+    // Call when the user completes the drag operation by dropping the object
+    // onto the page.
+    //
+    EXPORT void page_drag_target_drop(void* browser, int x, int y);
+
+    //
+    // Call when a drag started by the page ended, either in a drop at |x| and
+    // |y| with |operation| or cancelled with operation 0.
+    //
+    EXPORT void page_drag_source_ended(void* browser, int x, int y, uint32_t operation);
+
+    //
+    // Print the page to the PDF file at path, the callback is called exactly once
+    // when the file is written or printing failed.
+    //
+    EXPORT void page_print_to_pdf(void* browser,
+                                  const char* path,
+                                  const PdfOptions* options,
+                                  PrintToPdfCallback callback,
+                                  void* ctx);
+
     EXPORT const void* page_get_hwnd(void* browser);
 
-    EXPORT void page_send_ime_composition(void* browser, const char* input);
+    //
+    // Begins a new composition or updates the existing composition. The
+    // |underlines| and the |replacement_range| and |selection_range| ranges are
+    // optional and may be null.
+    //
+    EXPORT void page_send_ime_set_composition(void* browser,
+                                              const char* text,
+                                              const ImeUnderline* underlines,
+                                              size_t underlines_size,
+                                              const ImeRange* replacement_range,
+                                              const ImeRange* selection_range);
 
-    EXPORT void page_send_ime_set_composition(void* browser, const char* input, int x, int y);
+    //
+    // Completes the existing composition by optionally inserting |text| into the
+    // composition node. |relative_cursor_pos| is where the cursor will be
+    // positioned relative to the current cursor position.
+    //
+    EXPORT void page_send_ime_commit_text(void* browser,
+                                          const char* text,
+                                          const ImeRange* replacement_range,
+                                          int relative_cursor_pos);
+
+    //
+    // Completes the existing composition by applying the current composition
+    // node contents. If |keep_selection| is false the current selection, if any,
+    // will be discarded.
+    //
+    EXPORT void page_send_ime_finish_composing_text(void* browser, bool keep_selection);
+
+    //
+    // Cancels the existing composition and discards the composition node
+    // contents without applying them.
+    //
+    EXPORT void page_send_ime_cancel_composition(void* browser);
+
+    //
+    // Set the proxy of the request context used by the page.
+    //
+    EXPORT void page_set_proxy(void* browser, const ProxyOptions* proxy);
+
+    //
+    // Continue the authentication request, the callback is released.
+    //
+    EXPORT void auth_callback_continue(void* callback, const char* username, const char* password);
+
+    //
+    // Cancel the authentication request, the callback is released.
+    //
+    EXPORT void auth_callback_cancel(void* callback);
+
+    //
+    // Continue the download. Set |path| to the full file path for the download
+    // including the file name or to null to use the suggested name and the
+    // default temp directory. Set |show_dialog| to true if you do wish to show
+    // the default "Save As" dialog. The callback is released.
+    //
+    EXPORT void before_download_callback_continue(void* callback, const char* path, bool show_dialog);
+
+    //
+    // Reject the download, the callback is released.
+    //
+    EXPORT void before_download_callback_cancel(void* callback);
+
+    //
+    // Cancel the download.
+    //
+    EXPORT void download_callback_cancel(void* callback);
+
+    //
+    // Pause the download.
+    //
+    EXPORT void download_callback_pause(void* callback);
+
+    //
+    // Resume the download.
+    //
+    EXPORT void download_callback_resume(void* callback);
+
+    EXPORT void download_callback_release(void* callback);
+
+    //
+    // Allow the request despite the certificate error, the callback is
+    // released.
+    //
+    EXPORT void certificate_error_callback_continue(void* callback);
+
+    //
+    // Deny the request, the callback is released.
+    //
+    EXPORT void certificate_error_callback_cancel(void* callback);
+
+    //
+    // Select the client certificate at |index| of the certificates passed to
+    // the observer, a negative |index| continues without a certificate. The
+    // callback is released.
+    //
+    EXPORT void select_client_certificate_callback_select(void* callback, int index);
 
 #ifdef __cplusplus
 }
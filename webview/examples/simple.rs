@@ -6,17 +6,17 @@ use std::{
 
 use minifb::{MouseButton, MouseMode, Window, WindowOptions};
 use webview::{
-    execute_subprocess, is_subprocess, ActionState, MouseAction, MouseButtons, Observer,
-    PageOptions, Position, Webview, WebviewOptions,
+    execute_subprocess, is_subprocess, ActionState, Frame, Modifiers, MouseAction, MouseButtons,
+    Observer, PageOptions, Position, Webview, WebviewOptions,
 };
 
 struct PageObserver {
-    sender: Sender<Vec<u8>>,
+    sender: Sender<Vec<u32>>,
 }
 
 impl Observer for PageObserver {
-    fn on_frame(&self, buf: &[u8], _: u32, _: u32) {
-        self.sender.send(buf.to_vec()).unwrap();
+    fn on_frame(&self, frame: &Frame) {
+        self.sender.send(frame.to_argb32()).unwrap();
     }
 }
 
@@ -26,6 +26,8 @@ fn run_cef() -> anyhow::Result<()> {
         cache_path: None,
         browser_subprocess_path: None,
         scheme_path: None,
+        proxy: None,
+        background_color: 0x00FFFFFF,
     })?;
 
     let settings = PageOptions {
@@ -35,6 +37,10 @@ fn run_cef() -> anyhow::Result<()> {
         device_scale_factor: 1.0,
         is_offscreen: false,
         window_handle: None,
+        proxy: None,
+        content_blocker: None,
+        background_color: 0x00FFFFFF,
+        external_begin_frame: false,
     };
 
     let browser = app.create_page("https://google.com", &settings, PageObserver { sender })?;
@@ -50,24 +56,32 @@ fn run_cef() -> anyhow::Result<()> {
             1000 / settings.frame_rate as u64,
         )));
 
-        let mut frame = vec![0u8; (settings.width * settings.height * 4) as usize];
+        let mut frame = vec![0u32; (settings.width * settings.height) as usize];
         loop {
             if let Some((x, y)) = window
                 .get_mouse_pos(MouseMode::Clamp)
                 .map(|(x, y)| (x as i32, y as i32))
             {
                 if window.get_mouse_down(MouseButton::Left) {
-                    browser.on_mouse(MouseAction::Click(
-                        MouseButtons::kLeft,
-                        ActionState::Down,
-                        Some(Position { x, y }),
-                    ));
+                    browser.on_mouse(
+                        MouseAction::Click {
+                            button: MouseButtons::kLeft,
+                            state: ActionState::Down,
+                            position: Some(Position { x, y }),
+                            click_count: 1,
+                        },
+                        Modifiers::empty(),
+                    );
 
-                    browser.on_mouse(MouseAction::Click(
-                        MouseButtons::kLeft,
-                        ActionState::Up,
-                        None,
-                    ));
+                    browser.on_mouse(
+                        MouseAction::Click {
+                            button: MouseButtons::kLeft,
+                            state: ActionState::Up,
+                            position: None,
+                            click_count: 1,
+                        },
+                        Modifiers::empty(),
+                    );
                 }
             }
 
@@ -75,8 +89,7 @@ fn run_cef() -> anyhow::Result<()> {
                 frame = f;
             }
 
-            let (_, shorts, _) = unsafe { frame.align_to::<u32>() };
-            window.update_with_buffer(shorts, settings.width as usize, settings.height as usize)?;
+            window.update_with_buffer(&frame, settings.width as usize, settings.height as usize)?;
             thread::sleep(Duration::from_millis(1000 / settings.frame_rate as u64));
         }
 
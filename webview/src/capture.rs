@@ -0,0 +1,209 @@
+use std::time::{Duration, Instant};
+
+use webview_sys::Rect;
+
+use crate::{AlphaMode, Frame, PixelFormat};
+
+/// Options of `Page::capture`.
+#[derive(Debug, Clone, Copy)]
+pub struct CaptureOptions {
+    /// The region to capture in view coordinates, the whole view if not set.
+    ///
+    /// With |full_page| the region is relative to the top of the document.
+    pub clip: Option<Rect>,
+    /// Capture the whole document instead of the visible view.
+    ///
+    /// The view is temporarily resized to the content size reported by the
+    /// DevTools protocol and restored afterwards. The capture is limited to
+    /// 16384 pixels in each dimension.
+    pub full_page: bool,
+    /// How long to wait for the frame.
+    pub timeout: Duration,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            clip: None,
+            full_page: false,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// A captured image as tightly packed RGBA with straight alpha.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    /// Copy the |clip| region in view coordinates of the frame, or the whole
+    /// frame if not set.
+    pub fn from_frame(frame: &Frame, clip: Option<Rect>) -> Self {
+        let scale = frame.device_scale_factor;
+        let (x, y, right, bottom) = match clip {
+            Some(rect) => (
+                ((rect.x as f32 * scale) as u32).min(frame.width),
+                ((rect.y as f32 * scale) as u32).min(frame.height),
+                ((rect.x.saturating_add(rect.width) as f32 * scale).ceil() as u32).min(frame.width),
+                ((rect.y.saturating_add(rect.height) as f32 * scale).ceil() as u32)
+                    .min(frame.height),
+            ),
+            None => (0, 0, frame.width, frame.height),
+        };
+
+        // A negative size ends the clip before it starts.
+        let right = right.max(x);
+        let bottom = bottom.max(y);
+        let width = right - x;
+        let height = bottom - y;
+        let bytes_per_pixel = frame.format.bytes_per_pixel();
+
+        // Crop the frame first, so only the region is converted.
+        let mut buffer = Vec::with_capacity(width as usize * height as usize * bytes_per_pixel);
+        for row in y..bottom {
+            let row = frame.row(row);
+            buffer.extend_from_slice(
+                &row[x as usize * bytes_per_pixel..right as usize * bytes_per_pixel],
+            );
+        }
+
+        let region = Frame {
+            buffer: &buffer,
+            width,
+            height,
+            stride: width * bytes_per_pixel as u32,
+            dirty_rects: &[],
+            ..*frame
+        };
+
+        Self {
+            width,
+            height,
+            data: region.to_rgba(AlphaMode::Straight),
+        }
+    }
+
+    /// Copy a tightly packed premultiplied BGRA buffer of chromium.
+    pub(crate) fn from_bgra(buffer: &[u8], width: u32, height: u32) -> Self {
+        Self::from_frame(
+            &Frame {
+                buffer,
+                width,
+                height,
+                stride: width * 4,
+                format: PixelFormat::Bgra,
+                alpha: AlphaMode::Premultiplied,
+                device_scale_factor: 1.0,
+                timestamp: Instant::now(),
+                sequence: 0,
+                begin_frame: None,
+                dirty_rects: &[],
+            },
+            None,
+        )
+    }
+}
+
+#[cfg(feature = "encoder")]
+mod encoder {
+    use std::io::Cursor;
+
+    use image::{codecs::jpeg::JpegEncoder, ExtendedColorType, ImageEncoder, RgbaImage};
+
+    use super::Image;
+    use crate::Error;
+
+    /// The encoding of `Image::encode`.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+    pub enum ImageFormat {
+        Png,
+        /// JPEG with a quality from 1 to 100, the alpha channel is dropped.
+        Jpeg(u8),
+        /// Lossless WebP.
+        WebP,
+    }
+
+    impl Image {
+        /// Encode the image.
+        pub fn encode(&self, format: ImageFormat) -> Result<Vec<u8>, Error> {
+            let mut output = Cursor::new(Vec::new());
+            match format {
+                ImageFormat::Png => self
+                    .to_rgba_image()
+                    .and_then(|it| it.write_to(&mut output, image::ImageFormat::Png)),
+                ImageFormat::WebP => self
+                    .to_rgba_image()
+                    .and_then(|it| it.write_to(&mut output, image::ImageFormat::WebP)),
+                ImageFormat::Jpeg(quality) => {
+                    let rgb = self
+                        .data
+                        .chunks_exact(4)
+                        .flat_map(|it| [it[0], it[1], it[2]])
+                        .collect::<Vec<_>>();
+
+                    JpegEncoder::new_with_quality(&mut output, quality.clamp(1, 100)).write_image(
+                        &rgb,
+                        self.width,
+                        self.height,
+                        ExtendedColorType::Rgb8,
+                    )
+                }
+            }
+            .map_err(Error::EncodeImageError)?;
+
+            Ok(output.into_inner())
+        }
+
+        fn to_rgba_image(&self) -> Result<RgbaImage, image::ImageError> {
+            RgbaImage::from_raw(self.width, self.height, self.data.clone()).ok_or_else(|| {
+                image::ImageError::Parameter(image::error::ParameterError::from_kind(
+                    image::error::ParameterErrorKind::DimensionMismatch,
+                ))
+            })
+        }
+    }
+}
+
+#[cfg(feature = "encoder")]
+pub use self::encoder::ImageFormat;
+
+pub(crate) mod wrapper {
+    use std::sync::mpsc::Sender;
+
+    use webview_sys::Rect;
+
+    use super::Image;
+    use crate::Frame;
+
+    /// A capture waiting for the next complete frame.
+    pub(crate) struct PendingCapture {
+        /// The expected view size of the frame, a full page capture ignores
+        /// the frames painted before the resize is applied.
+        pub size: Option<(u32, u32)>,
+        pub clip: Option<Rect>,
+        pub sender: Sender<Image>,
+    }
+
+    impl PendingCapture {
+        /// Send the image if the frame is the expected one, returns false if
+        /// the capture is still pending.
+        pub(crate) fn resolve(&self, frame: &Frame) -> bool {
+            if let Some((width, height)) = self.size {
+                let scale = frame.device_scale_factor;
+                if (frame.width as f32 / scale).round() as u32 != width
+                    || (frame.height as f32 / scale).round() as u32 != height
+                {
+                    return false;
+                }
+            }
+
+            // The receiver may be gone after a timeout.
+            let _ = self.sender.send(Image::from_frame(frame, self.clip));
+            true
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::{collections::HashSet, ffi::c_void, fmt};
+
+use sha2::{Digest, Sha256};
+use webview_sys::{
+    certificate_error_callback_cancel, certificate_error_callback_continue,
+    select_client_certificate_callback_select,
+};
+
+use crate::Error;
+
+/// A X.509 certificate.
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    /// The display name of the subject.
+    pub subject: String,
+    /// The display name of the issuer.
+    pub issuer: String,
+    /// The DER encoded data of the certificate.
+    pub der: Vec<u8>,
+}
+
+impl Certificate {
+    /// The SHA-256 fingerprint of the DER encoded certificate.
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint(Sha256::digest(&self.der).into())
+    }
+}
+
+/// SHA-256 fingerprint of a certificate.
+///
+/// It is formatted as colon separated uppercase hex, the same form browsers
+/// and `openssl x509 -fingerprint -sha256` show.
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Fingerprint(pub [u8; 32]);
+
+impl Fingerprint {
+    /// Parse a fingerprint from hex, the bytes may be separated by colons or
+    /// spaces.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let digits = value
+            .chars()
+            .filter(|it| *it != ':' && !it.is_whitespace())
+            .map(|it| it.to_digit(16).map(|it| it as u8))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::InvalidFingerprint)?;
+
+        if digits.len() != 64 {
+            return Err(Error::InvalidFingerprint);
+        }
+
+        let mut bytes = [0u8; 32];
+        for (i, it) in digits.chunks(2).enumerate() {
+            bytes[i] = (it[0] << 4) | it[1];
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, it) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ":")?;
+            }
+
+            write!(f, "{:02X}", it)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fingerprint({})", self)
+    }
+}
+
+/// A certificate error of a request.
+#[derive(Debug, Clone)]
+pub struct CertificateError {
+    /// The url of the request.
+    pub url: String,
+    /// The net error code, e.g. -202 for ERR_CERT_AUTHORITY_INVALID.
+    pub code: i32,
+    /// The certificate presented by the server, if any.
+    pub certificate: Option<Certificate>,
+}
+
+/// Callback interface used to allow or deny a request with a certificate
+/// error.
+///
+/// The callback can be answered from any thread. Dropping the callback
+/// without answering denies the request.
+pub struct CertificateErrorCallback(*mut c_void);
+
+unsafe impl Send for CertificateErrorCallback {}
+unsafe impl Sync for CertificateErrorCallback {}
+
+impl CertificateErrorCallback {
+    pub(crate) fn new(raw: *mut c_void) -> Self {
+        Self(raw)
+    }
+
+    /// Allow the request despite the certificate error.
+    pub fn allow(mut self) {
+        unsafe { certificate_error_callback_continue(self.0) }
+        self.0 = std::ptr::null_mut();
+    }
+
+    /// Deny the request.
+    pub fn deny(self) {
+        drop(self)
+    }
+}
+
+impl Drop for CertificateErrorCallback {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { certificate_error_callback_cancel(self.0) }
+        }
+    }
+}
+
+/// Callback interface used to select a client certificate.
+///
+/// The callback can be answered from any thread. Dropping the callback
+/// without answering continues without a client certificate.
+pub struct SelectClientCertificateCallback(*mut c_void);
+
+unsafe impl Send for SelectClientCertificateCallback {}
+unsafe impl Sync for SelectClientCertificateCallback {}
+
+impl SelectClientCertificateCallback {
+    pub(crate) fn new(raw: *mut c_void) -> Self {
+        Self(raw)
+    }
+
+    /// Select the certificate at |index| of the offered certificates, or
+    /// `None` to continue without a client certificate.
+    pub fn select(mut self, index: Option<usize>) {
+        let index = index.map(|it| it as i32).unwrap_or(-1);
+
+        unsafe { select_client_certificate_callback_select(self.0, index) }
+        self.0 = std::ptr::null_mut();
+    }
+}
+
+impl Drop for SelectClientCertificateCallback {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { select_client_certificate_callback_select(self.0, -1) }
+        }
+    }
+}
+
+/// An allowlist of pinned certificate fingerprints.
+///
+/// Useful for staging services with self-signed certificates: the
+/// certificate error is allowed only if the server presents one of the pinned
+/// certificates.
+#[derive(Debug, Default, Clone)]
+pub struct CertificatePins(HashSet<Fingerprint>);
+
+impl CertificatePins {
+    /// Add a fingerprint in hex, see `Fingerprint::parse`.
+    pub fn add(&mut self, fingerprint: &str) -> Result<(), Error> {
+        self.0.insert(Fingerprint::parse(fingerprint)?);
+        Ok(())
+    }
+
+    /// Whether the certificate is pinned.
+    pub fn contains(&self, certificate: &Certificate) -> bool {
+        self.0.contains(&certificate.fingerprint())
+    }
+
+    /// Allow the request if the certificate of the error is pinned, otherwise
+    /// deny it.
+    pub fn resolve(&self, error: &CertificateError, callback: CertificateErrorCallback) {
+        match &error.certificate {
+            Some(it) if self.contains(it) => callback.allow(),
+            _ => callback.deny(),
+        }
+    }
+}
+
+pub(crate) mod wrapper {
+    use std::slice::from_raw_parts;
+
+    use super::Certificate;
+    use crate::ffi;
+
+    impl From<&webview_sys::Certificate> for Certificate {
+        fn from(certificate: &webview_sys::Certificate) -> Self {
+            Self {
+                subject: ffi::from(certificate.subject).unwrap_or_default(),
+                issuer: ffi::from(certificate.issuer).unwrap_or_default(),
+                der: if certificate.der.is_null() {
+                    Vec::new()
+                } else {
+                    unsafe { from_raw_parts(certificate.der, certificate.der_size) }.to_vec()
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Certificate, CertificatePins, Fingerprint};
+
+    const ABC: &str = "BA:78:16:BF:8F:01:CF:EA:41:41:40:DE:5D:AE:22:23:B0:03:61:A3:96:17:7A:9C:B4:10:FF:61:F2:00:15:AD";
+
+    fn certificate(der: &[u8]) -> Certificate {
+        Certificate {
+            subject: String::new(),
+            issuer: String::new(),
+            der: der.to_vec(),
+        }
+    }
+
+    #[test]
+    fn fingerprint_known_answers() {
+        // FIPS 180-4 examples.
+        let vectors = [
+            (
+                &b""[..],
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                &b"abc"[..],
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                &b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"[..],
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+
+        for (der, hex) in vectors {
+            assert_eq!(
+                certificate(der).fingerprint(),
+                Fingerprint::parse(hex).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_forms() {
+        let colon = Fingerprint::parse(ABC).unwrap();
+        assert_eq!(colon.to_string(), ABC);
+
+        let hex = ABC.replace(':', "");
+        assert_eq!(Fingerprint::parse(&hex).unwrap(), colon);
+        assert_eq!(Fingerprint::parse(&hex.to_lowercase()).unwrap(), colon);
+        assert_eq!(Fingerprint::parse(&ABC.replace(':', " ")).unwrap(), colon);
+    }
+
+    #[test]
+    fn parse_rejects_wrong_length() {
+        let hex = ABC.replace(':', "");
+
+        assert!(Fingerprint::parse("").is_err());
+        assert!(Fingerprint::parse(&hex[..62]).is_err());
+        assert!(Fingerprint::parse(&format!("{}00", hex)).is_err());
+        assert!(Fingerprint::parse(&hex[..63]).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_bad_characters() {
+        let hex = ABC.replace(':', "");
+
+        assert!(Fingerprint::parse(&format!("G{}", &hex[1..])).is_err());
+        assert!(Fingerprint::parse(&format!("-{}", &hex[1..])).is_err());
+        assert!(Fingerprint::parse(&ABC.replace(':', "-")).is_err());
+    }
+
+    #[test]
+    fn pins() {
+        let mut pins = CertificatePins::default();
+        assert!(!pins.contains(&certificate(b"abc")));
+
+        pins.add(ABC).unwrap();
+        assert!(pins.contains(&certificate(b"abc")));
+        assert!(!pins.contains(&certificate(b"abd")));
+        assert!(!pins.contains(&certificate(b"")));
+
+        pins.add(&ABC.replace(':', "").to_lowercase()).unwrap();
+        assert!(pins.add("BA:78").is_err());
+        assert!(pins.add("not a fingerprint").is_err());
+        assert!(pins.contains(&certificate(b"abc")));
+    }
+}
@@ -0,0 +1,293 @@
+/// The number of input events merged into a later event of the same kind,
+/// see `Page::set_input_coalescing`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct CoalescedInput {
+    pub mouse_moves: u64,
+    pub mouse_wheels: u64,
+    pub touch_moves: u64,
+}
+
+pub(crate) mod wrapper {
+    use webview_sys::TouchEventType;
+
+    use super::CoalescedInput;
+    use crate::{Modifiers, MouseAction, TouchEvent, WheelDelta};
+
+    pub(crate) enum PendingInput {
+        Mouse(MouseAction, Modifiers),
+        Touch(TouchEvent),
+    }
+
+    /// Holds back mouse moves, mouse wheels and touch moves until the next
+    /// frame, so only the last move and the sum of the wheel deltas are sent.
+    ///
+    /// Any other input sends the held back events first, so button
+    /// transitions see the position they happened at and the order of the
+    /// input is kept.
+    #[derive(Default)]
+    pub(crate) struct InputCoalescer {
+        pub enabled: bool,
+        pub counts: CoalescedInput,
+        pending: Vec<PendingInput>,
+    }
+
+    impl InputCoalescer {
+        /// The events to send now for a mouse event.
+        pub(crate) fn mouse(
+            &mut self,
+            action: MouseAction,
+            modifiers: Modifiers,
+        ) -> Vec<PendingInput> {
+            if !self.enabled || !matches!(action, MouseAction::Move(_) | MouseAction::Wheel { .. })
+            {
+                return self.flush_with(PendingInput::Mouse(action, modifiers));
+            }
+
+            // Only merge with the last event, a move after a wheel must not
+            // overtake it.
+            if let Some(PendingInput::Mouse(last, last_modifiers)) = self.pending.last_mut() {
+                if *last_modifiers == modifiers {
+                    match (last, &action) {
+                        (MouseAction::Move(last), MouseAction::Move(position)) => {
+                            *last = *position;
+                            self.counts.mouse_moves += 1;
+                            return Vec::new();
+                        }
+                        (
+                            MouseAction::Wheel {
+                                position: last_position,
+                                delta: last_delta,
+                            },
+                            MouseAction::Wheel { position, delta },
+                        ) => {
+                            *last_position = *position;
+                            *last_delta = match (*last_delta, *delta) {
+                                (WheelDelta::Line(x, y), WheelDelta::Line(dx, dy)) => {
+                                    WheelDelta::Line(x + dx, y + dy)
+                                }
+                                (WheelDelta::Pixel(x, y), WheelDelta::Pixel(dx, dy)) => {
+                                    WheelDelta::Pixel(x + dx, y + dy)
+                                }
+                                (last_delta, delta) => {
+                                    let (x, y) = last_delta.to_pixels();
+                                    let (dx, dy) = delta.to_pixels();
+                                    WheelDelta::Pixel((x + dx) as f32, (y + dy) as f32)
+                                }
+                            };
+
+                            self.counts.mouse_wheels += 1;
+                            return Vec::new();
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            self.pending.push(PendingInput::Mouse(action, modifiers));
+            Vec::new()
+        }
+
+        /// The events to send now for a touch event.
+        pub(crate) fn touch(&mut self, event: TouchEvent) -> Vec<PendingInput> {
+            if !self.enabled || event.ty != TouchEventType::kTouchMoved {
+                return self.flush_with(PendingInput::Touch(event));
+            }
+
+            // The moves of the other touch points of a gesture are in between,
+            // the points move independently so the last move of this point
+            // can be replaced as long as only moves of other points follow it.
+            let last = self.pending.iter().rposition(|it| match it {
+                PendingInput::Touch(it) => it.id == event.id,
+                PendingInput::Mouse(..) => true,
+            });
+
+            if let Some(index) = last {
+                if let PendingInput::Touch(it) = &self.pending[index] {
+                    if it.id == event.id && it.modifiers == event.modifiers {
+                        self.pending.remove(index);
+                        self.counts.touch_moves += 1;
+                    }
+                }
+            }
+
+            self.pending.push(PendingInput::Touch(event));
+            Vec::new()
+        }
+
+        /// The held back events, to send them before other input or at a
+        /// frame.
+        pub(crate) fn flush(&mut self) -> Vec<PendingInput> {
+            std::mem::take(&mut self.pending)
+        }
+
+        fn flush_with(&mut self, input: PendingInput) -> Vec<PendingInput> {
+            let mut events = self.flush();
+            events.push(input);
+            events
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use webview_sys::{MouseButtons, TouchEventType};
+
+    use super::wrapper::{InputCoalescer, PendingInput};
+    use crate::{ActionState, Modifiers, MouseAction, Position, TouchEvent, WheelDelta};
+
+    fn coalescer() -> InputCoalescer {
+        let mut coalescer = InputCoalescer::default();
+        coalescer.enabled = true;
+        coalescer
+    }
+
+    fn click(state: ActionState) -> MouseAction {
+        MouseAction::Click {
+            button: MouseButtons::kLeft,
+            state,
+            position: None,
+            click_count: 1,
+        }
+    }
+
+    fn wheel(x: i32, dy: f32) -> MouseAction {
+        MouseAction::Wheel {
+            position: Position { x, y: 0 },
+            delta: WheelDelta::Pixel(0.0, dy),
+        }
+    }
+
+    /// A short description of the sent events to compare them.
+    fn describe(events: Vec<PendingInput>) -> Vec<String> {
+        events
+            .into_iter()
+            .map(|it| match it {
+                PendingInput::Mouse(MouseAction::Move(position), _) => {
+                    format!("move {}", position.x)
+                }
+                PendingInput::Mouse(MouseAction::Wheel { position, delta }, _) => {
+                    format!("wheel {} {:?}", position.x, delta.to_pixels())
+                }
+                PendingInput::Mouse(MouseAction::Click { state, .. }, _) => {
+                    format!("click {:?}", state)
+                }
+                PendingInput::Mouse(MouseAction::Leave, _) => "leave".to_string(),
+                PendingInput::Touch(event) => {
+                    format!("touch {} {:?} {}", event.id, event.ty, event.x)
+                }
+            })
+            .collect()
+    }
+
+    fn mouse(coalescer: &mut InputCoalescer, action: MouseAction) -> Vec<String> {
+        describe(coalescer.mouse(action, Modifiers::empty()))
+    }
+
+    #[test]
+    fn disabled_sends_everything() {
+        let mut coalescer = InputCoalescer::default();
+
+        assert_eq!(
+            mouse(&mut coalescer, MouseAction::Move(Position { x: 1, y: 0 })),
+            ["move 1"]
+        );
+        assert_eq!(
+            mouse(&mut coalescer, MouseAction::Move(Position { x: 2, y: 0 })),
+            ["move 2"]
+        );
+        assert_eq!(coalescer.counts.mouse_moves, 0);
+    }
+
+    #[test]
+    fn moves_merge_until_a_click() {
+        let mut coalescer = coalescer();
+
+        for x in 1..=3 {
+            assert!(mouse(&mut coalescer, MouseAction::Move(Position { x, y: 0 })).is_empty());
+        }
+
+        assert_eq!(
+            mouse(&mut coalescer, click(ActionState::Down)),
+            ["move 3", "click Down"]
+        );
+
+        assert_eq!(coalescer.counts.mouse_moves, 2);
+        assert!(coalescer.flush().is_empty());
+    }
+
+    #[test]
+    fn wheels_sum_without_passing_moves() {
+        let mut coalescer = coalescer();
+
+        mouse(&mut coalescer, wheel(1, 10.0));
+        mouse(&mut coalescer, wheel(2, 5.0));
+        mouse(&mut coalescer, MouseAction::Move(Position { x: 3, y: 0 }));
+        mouse(&mut coalescer, wheel(4, 1.0));
+
+        assert_eq!(
+            describe(coalescer.flush()),
+            ["wheel 2 (0, 15)", "move 3", "wheel 4 (0, 1)"]
+        );
+
+        assert_eq!(coalescer.counts.mouse_wheels, 1);
+    }
+
+    #[test]
+    fn button_transitions_keep_their_position() {
+        let mut coalescer = coalescer();
+
+        mouse(&mut coalescer, MouseAction::Move(Position { x: 1, y: 0 }));
+        assert_eq!(
+            mouse(&mut coalescer, click(ActionState::Down)),
+            ["move 1", "click Down"]
+        );
+
+        mouse(&mut coalescer, MouseAction::Move(Position { x: 2, y: 0 }));
+        mouse(&mut coalescer, MouseAction::Move(Position { x: 3, y: 0 }));
+        assert_eq!(
+            mouse(&mut coalescer, click(ActionState::Up)),
+            ["move 3", "click Up"]
+        );
+
+        assert_eq!(mouse(&mut coalescer, MouseAction::Leave), ["leave"]);
+    }
+
+    #[test]
+    fn touch_moves_merge_per_point() {
+        let mut coalescer = coalescer();
+        let touch = |id, x, ty| TouchEvent::new(id, x, 0.0, ty);
+
+        for (id, x) in [(0, 1.0), (1, 1.0), (0, 2.0), (1, 2.0)] {
+            assert!(coalescer
+                .touch(touch(id, x, TouchEventType::kTouchMoved))
+                .is_empty());
+        }
+
+        assert_eq!(
+            describe(coalescer.touch(touch(0, 3.0, TouchEventType::kTouchReleased))),
+            [
+                "touch 0 kTouchMoved 2",
+                "touch 1 kTouchMoved 2",
+                "touch 0 kTouchReleased 3"
+            ]
+        );
+
+        assert_eq!(coalescer.counts.touch_moves, 2);
+    }
+
+    #[test]
+    fn touch_moves_do_not_pass_mouse_input() {
+        let mut coalescer = coalescer();
+
+        coalescer.touch(TouchEvent::new(0, 1.0, 0.0, TouchEventType::kTouchMoved));
+        mouse(&mut coalescer, MouseAction::Move(Position { x: 1, y: 0 }));
+        coalescer.touch(TouchEvent::new(0, 2.0, 0.0, TouchEventType::kTouchMoved));
+
+        assert_eq!(
+            describe(coalescer.flush()),
+            ["touch 0 kTouchMoved 1", "move 1", "touch 0 kTouchMoved 2"]
+        );
+
+        assert_eq!(coalescer.counts.touch_moves, 0);
+    }
+}
@@ -0,0 +1,889 @@
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use webview_sys::ResourceType;
+
+/// A content blocking engine for Adblock Plus (EasyList) style filter lists.
+///
+/// The engine is consulted on every resource request of the pages it is
+/// attached to, see `PageOptions::content_blocker`. Supported are the request
+/// blocking rules of the filter syntax:
+///
+/// * `||example.com^` domain anchors, `|` start and end anchors, `*`
+///   wildcards and `^` separators.
+/// * `@@` exception rules, `@@...$document` disables blocking on matching
+///   pages.
+/// * The resource type options `script`, `image`, `stylesheet`, `object`,
+///   `xmlhttprequest`, `subdocument`, `document`, `media`, `font`, `ping`
+///   and `other`, including their `~` inverses.
+/// * The `third-party`, `domain=` and `match-case` options.
+///
+/// Element hiding rules, regular expression rules and rules with unsupported
+/// options are ignored.
+#[derive(Default)]
+pub struct ContentBlocker {
+    rules: Vec<Rule>,
+    tokens: HashMap<String, Vec<usize>>,
+    untokenized: Vec<usize>,
+    document_exceptions: Vec<usize>,
+}
+
+impl fmt::Debug for ContentBlocker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContentBlocker")
+            .field("rules", &self.rules.len())
+            .finish()
+    }
+}
+
+impl ContentBlocker {
+    /// Create an engine from the text of a filter list.
+    pub fn new(list: &str) -> Self {
+        let mut blocker = Self::default();
+        blocker.add_rules(list);
+        blocker
+    }
+
+    /// Create an engine from a local filter list file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(&fs::read_to_string(path)?))
+    }
+
+    /// Add the rules of a filter list, one rule per line.
+    pub fn add_rules(&mut self, list: &str) {
+        for line in list.lines() {
+            if let Some(rule) = Rule::parse(line) {
+                let index = self.rules.len();
+                if rule.is_exception
+                    && rule.types & type_mask(ResourceType::kResourceMainFrame) != 0
+                {
+                    self.document_exceptions.push(index);
+                }
+
+                match rule.token() {
+                    Some(token) => self.tokens.entry(token).or_default().push(index),
+                    None => self.untokenized.push(index),
+                }
+
+                self.rules.push(rule);
+            }
+        }
+    }
+
+    /// The number of loaded request blocking rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether the request of |url| with the resource type |ty|, made by the
+    /// document at |source_url|, should be blocked.
+    pub fn should_block(&self, url: &str, source_url: &str, ty: ResourceType) -> bool {
+        let request = Request::new(url, source_url, ty);
+        if request.host.is_empty() {
+            return false;
+        }
+
+        // Pages allowed by `@@...$document` exceptions are not filtered at all.
+        if self
+            .document_exceptions
+            .iter()
+            .any(|it| self.rules[*it].matches_document(&request))
+        {
+            return false;
+        }
+
+        let candidates = self.candidates(&request.lowercase_url);
+        candidates
+            .iter()
+            .any(|it| !it.is_exception && it.matches(&request))
+            && !candidates
+                .iter()
+                .any(|it| it.is_exception && it.matches(&request))
+    }
+
+    fn candidates(&self, url: &str) -> Vec<&Rule> {
+        let mut indexes = url_tokens(url)
+            .filter_map(|it| self.tokens.get(it))
+            .flatten()
+            .chain(self.untokenized.iter())
+            .copied()
+            .collect::<Vec<_>>();
+
+        indexes.sort_unstable();
+        indexes.dedup();
+        indexes.into_iter().map(|it| &self.rules[it]).collect()
+    }
+}
+
+struct Request<'a> {
+    url: &'a str,
+    lowercase_url: String,
+    host: String,
+    source_url: &'a str,
+    lowercase_source_url: String,
+    source_host: String,
+    ty: ResourceType,
+    is_third_party: bool,
+}
+
+impl<'a> Request<'a> {
+    fn new(url: &'a str, source_url: &'a str, ty: ResourceType) -> Self {
+        let lowercase_url = url.to_lowercase();
+        let lowercase_source_url = source_url.to_lowercase();
+        let host = host_of(&lowercase_url).to_string();
+        let source_host = host_of(&lowercase_source_url).to_string();
+        let is_third_party =
+            !source_host.is_empty() && base_domain(&host) != base_domain(&source_host);
+
+        Self {
+            url,
+            lowercase_url,
+            host,
+            source_url,
+            lowercase_source_url,
+            source_host,
+            ty,
+            is_third_party,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Token {
+    Literal(String),
+    Wildcard,
+    Separator,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Anchor {
+    None,
+    Start,
+    Domain,
+}
+
+#[derive(Debug)]
+struct Rule {
+    is_exception: bool,
+    anchor: Anchor,
+    is_end_anchored: bool,
+    tokens: Vec<Token>,
+    pattern: String,
+    types: u32,
+    third_party: Option<bool>,
+    include_domains: Vec<String>,
+    exclude_domains: Vec<String>,
+    match_case: bool,
+}
+
+/// The resource types a rule without type options applies to, everything
+/// except the document itself.
+const DEFAULT_TYPES: u32 = !((1 << ResourceType::kResourceMainFrame as u32)
+    | (1 << ResourceType::kResourceNavigationPreloadMainFrame as u32));
+
+/// The resource types of the `other` type option.
+const OTHER_TYPES: [ResourceType; 5] = [
+    ResourceType::kResourceSubResource,
+    ResourceType::kResourceWorker,
+    ResourceType::kResourceSharedWorker,
+    ResourceType::kResourcePrefetch,
+    ResourceType::kResourceServiceWorker,
+];
+
+fn type_mask(ty: ResourceType) -> u32 {
+    1 << ty as u32
+}
+
+/// Map a filter type option to the resource types, `None` if the option is
+/// not a type option.
+fn types_of_option(option: &str) -> Option<u32> {
+    use ResourceType::*;
+
+    let types: &[ResourceType] = match option {
+        "script" => &[kResourceScript],
+        "image" => &[kResourceImage, kResourceFavicon],
+        "stylesheet" | "css" => &[kResourceStylesheet],
+        "object" | "object-subrequest" => &[kResourceObject, kResourcePluginResource],
+        "xmlhttprequest" | "xhr" => &[kResourceXhr],
+        "subdocument" | "frame" => &[kResourceSubFrame, kResourceNavigationPreloadSubFrame],
+        "document" | "doc" => &[kResourceMainFrame, kResourceNavigationPreloadMainFrame],
+        "media" => &[kResourceMedia],
+        "font" => &[kResourceFont],
+        "ping" | "beacon" => &[kResourcePing, kResourceCspReport],
+        // Websocket requests never pass through the resource request handler.
+        "websocket" => &[],
+        "other" => &OTHER_TYPES,
+        _ => return None,
+    };
+
+    Some(types.iter().fold(0, |mask, it| mask | type_mask(*it)))
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+
+        // Comments, the list header and element hiding rules.
+        if line.is_empty()
+            || line.starts_with('!')
+            || line.starts_with('[')
+            || line.contains("##")
+            || line.contains("#@#")
+            || line.contains("#?#")
+            || line.contains("#$#")
+        {
+            return None;
+        }
+
+        let (is_exception, line) = match line.strip_prefix("@@") {
+            Some(it) => (true, it),
+            None => (false, line),
+        };
+
+        // Regular expression rules are not supported.
+        if line.starts_with('/') && line.ends_with('/') && line.len() > 1 {
+            return None;
+        }
+
+        let (pattern, options) = match line.rfind('$') {
+            Some(index) if !line[index + 1..].contains('/') => {
+                (&line[..index], Some(&line[index + 1..]))
+            }
+            _ => (line, None),
+        };
+
+        let mut rule = Self {
+            is_exception,
+            anchor: Anchor::None,
+            is_end_anchored: false,
+            tokens: Vec::new(),
+            pattern: String::new(),
+            types: DEFAULT_TYPES,
+            third_party: None,
+            include_domains: Vec::new(),
+            exclude_domains: Vec::new(),
+            match_case: false,
+        };
+
+        if let Some(options) = options {
+            let mut include_types = 0;
+            let mut exclude_types = 0;
+
+            for option in options.split(',') {
+                let option = option.trim().to_lowercase();
+                let (is_inverse, name) = match option.strip_prefix('~') {
+                    Some(it) => (true, it),
+                    None => (false, option.as_str()),
+                };
+
+                if let Some(types) = types_of_option(name) {
+                    if is_inverse {
+                        exclude_types |= types;
+                    } else {
+                        include_types |= types;
+                    }
+
+                    continue;
+                }
+
+                match name {
+                    "third-party" | "3p" => rule.third_party = Some(!is_inverse),
+                    "first-party" | "1p" => rule.third_party = Some(is_inverse),
+                    "match-case" => rule.match_case = true,
+                    _ => {
+                        if let Some(domains) = name.strip_prefix("domain=") {
+                            for domain in domains.split('|').filter(|it| !it.is_empty()) {
+                                match domain.strip_prefix('~') {
+                                    Some(it) => rule.exclude_domains.push(it.to_string()),
+                                    None => rule.include_domains.push(domain.to_string()),
+                                }
+                            }
+                        } else {
+                            // Rules with unsupported options are ignored, applying
+                            // them without the option could block too much.
+                            return None;
+                        }
+                    }
+                }
+            }
+
+            if include_types != 0 {
+                rule.types = include_types;
+            }
+
+            rule.types &= !exclude_types;
+        }
+
+        let mut pattern = pattern;
+        if let Some(it) = pattern.strip_prefix("||") {
+            rule.anchor = Anchor::Domain;
+            pattern = it;
+        } else if let Some(it) = pattern.strip_prefix('|') {
+            rule.anchor = Anchor::Start;
+            pattern = it;
+        }
+
+        if let Some(it) = pattern.strip_suffix('|') {
+            rule.is_end_anchored = true;
+            pattern = it;
+        }
+
+        rule.pattern = if rule.match_case {
+            pattern.to_string()
+        } else {
+            pattern.to_lowercase()
+        };
+
+        let mut literal = String::new();
+        for char in rule.pattern.chars() {
+            let token = match char {
+                '*' => Token::Wildcard,
+                '^' => Token::Separator,
+                _ => {
+                    literal.push(char);
+                    continue;
+                }
+            };
+
+            if !literal.is_empty() {
+                rule.tokens
+                    .push(Token::Literal(std::mem::take(&mut literal)));
+            }
+
+            // Consecutive wildcards are redundant.
+            if !(matches!(token, Token::Wildcard)
+                && matches!(rule.tokens.last(), Some(Token::Wildcard)))
+            {
+                rule.tokens.push(token);
+            }
+        }
+
+        if !literal.is_empty() {
+            rule.tokens.push(Token::Literal(literal));
+        }
+
+        Some(rule)
+    }
+
+    /// The longest run of token characters of the pattern that is certain to
+    /// appear as a whole token in a matching url.
+    fn token(&self) -> Option<String> {
+        if self.match_case {
+            return None;
+        }
+
+        let chars = self.pattern.chars().collect::<Vec<_>>();
+        let mut token: Option<String> = None;
+        let mut start = 0;
+
+        while start < chars.len() {
+            if !is_token_char(chars[start]) {
+                start += 1;
+                continue;
+            }
+
+            let mut end = start;
+            while end < chars.len() && is_token_char(chars[end]) {
+                end += 1;
+            }
+
+            let is_start_bounded = if start == 0 {
+                self.anchor != Anchor::None
+            } else {
+                chars[start - 1] != '*'
+            };
+
+            let is_end_bounded = if end == chars.len() {
+                self.is_end_anchored
+            } else {
+                chars[end] != '*'
+            };
+
+            if is_start_bounded
+                && is_end_bounded
+                && end - start > token.as_ref().map(|it| it.len()).unwrap_or(1)
+            {
+                token = Some(chars[start..end].iter().collect());
+            }
+
+            start = end;
+        }
+
+        token
+    }
+
+    fn matches(&self, request: &Request) -> bool {
+        if self.types & type_mask(request.ty) == 0 {
+            return false;
+        }
+
+        if let Some(third_party) = self.third_party {
+            if third_party != request.is_third_party {
+                return false;
+            }
+        }
+
+        if !self.matches_domain(&request.source_host) {
+            return false;
+        }
+
+        self.matches_url(request.url, &request.lowercase_url)
+    }
+
+    /// Whether the exception rule allows the whole document the request is
+    /// made by.
+    fn matches_document(&self, request: &Request) -> bool {
+        self.matches_domain(&request.source_host)
+            && self.matches_url(request.source_url, &request.lowercase_source_url)
+    }
+
+    fn matches_domain(&self, host: &str) -> bool {
+        let is_match = |domain: &String| host == domain || host.ends_with(&format!(".{}", domain));
+
+        if self.exclude_domains.iter().any(is_match) {
+            return false;
+        }
+
+        self.include_domains.is_empty() || self.include_domains.iter().any(is_match)
+    }
+
+    fn matches_url(&self, url: &str, lowercase_url: &str) -> bool {
+        let url = if self.match_case {
+            url.as_bytes()
+        } else {
+            lowercase_url.as_bytes()
+        };
+
+        // Cheap rejection before the full match.
+        for token in &self.tokens {
+            if let Token::Literal(it) = token {
+                if !contains(url, it.as_bytes()) {
+                    return false;
+                }
+            }
+        }
+
+        match self.anchor {
+            Anchor::Start => self.matches_at(url, 0, false),
+            Anchor::Domain => {
+                let (start, end) = host_range(url);
+                (start..end)
+                    .filter(|it| *it == start || url[it - 1] == b'.')
+                    .any(|it| self.matches_at(url, it, false))
+            }
+            // An unanchored pattern is the same as one with a leading wildcard.
+            Anchor::None => self.matches_at(url, 0, true),
+        }
+    }
+
+    /// Match the tokens against the url from |index|, as if the tokens start
+    /// with a wildcard if |is_floating|.
+    ///
+    /// Wildcards match greedily and only the last one is backtracked: every
+    /// token other than a wildcard matches at most one way at a given index,
+    /// so matching the part after a wildcard at the earliest possible index is
+    /// never worse than a later one. This keeps the match at O(url * tokens)
+    /// in the worst case, instead of exponential in the number of wildcards.
+    fn matches_at(&self, url: &[u8], mut index: usize, is_floating: bool) -> bool {
+        let mut token = 0;
+
+        // The token after the last wildcard and the index it was tried at.
+        let mut wildcard = is_floating.then_some((0, index));
+
+        loop {
+            let is_match = match self.tokens.get(token) {
+                None => !self.is_end_anchored || index == url.len(),
+                Some(Token::Wildcard) => {
+                    wildcard = Some((token + 1, index));
+                    token += 1;
+                    continue;
+                }
+                Some(Token::Literal(it)) => {
+                    if url[index..].starts_with(it.as_bytes()) {
+                        index += it.len();
+                        token += 1;
+                        continue;
+                    }
+
+                    false
+                }
+                Some(Token::Separator) => {
+                    // The end of the url is a separator as well.
+                    if index == url.len() {
+                        token += 1;
+                        continue;
+                    }
+
+                    if is_separator(url[index]) {
+                        index += 1;
+                        token += 1;
+                        continue;
+                    }
+
+                    false
+                }
+            };
+
+            if is_match {
+                return true;
+            }
+
+            // Let the last wildcard consume one more byte and retry the tokens
+            // after it.
+            match wildcard {
+                Some((next, start)) if start < url.len() => {
+                    wildcard = Some((next, start + 1));
+                    token = next;
+                    index = start + 1;
+                }
+                _ => return false,
+            }
+        }
+    }
+}
+
+fn is_token_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '%'
+}
+
+fn is_separator(byte: u8) -> bool {
+    !(byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-' | b'.' | b'%'))
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty() || haystack.windows(needle.len()).any(|it| it == needle)
+}
+
+fn url_tokens(url: &str) -> impl Iterator<Item = &str> {
+    url.split(|it: char| !is_token_char(it))
+        .filter(|it| it.len() > 1)
+}
+
+/// The byte range of the host in the url.
+fn host_range(url: &[u8]) -> (usize, usize) {
+    let start = url
+        .windows(3)
+        .position(|it| it == b"://")
+        .map(|it| it + 3)
+        .unwrap_or(0);
+
+    let authority = url[start..]
+        .iter()
+        .position(|it| matches!(it, b'/' | b'?' | b'#'))
+        .map(|it| start + it)
+        .unwrap_or(url.len());
+
+    // Skip the user info of the authority.
+    let start = url[start..authority]
+        .iter()
+        .rposition(|it| *it == b'@')
+        .map(|it| start + it + 1)
+        .unwrap_or(start);
+
+    let end = url[start..authority]
+        .iter()
+        .position(|it| *it == b':')
+        .map(|it| start + it)
+        .unwrap_or(authority);
+
+    (start, end)
+}
+
+fn host_of(url: &str) -> &str {
+    let (start, end) = host_range(url.as_bytes());
+    &url[start..end]
+}
+
+/// An approximation of the registrable domain without the public suffix list:
+/// the last two labels of the host.
+fn base_domain(host: &str) -> &str {
+    host.rmatch_indices('.')
+        .nth(1)
+        .map(|(index, _)| &host[index + 1..])
+        .unwrap_or(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use webview_sys::ResourceType::{self, *};
+
+    use super::{base_domain, ContentBlocker, Rule};
+
+    const PAGE: &str = "https://example.org/index.html";
+
+    fn blocks(list: &str, url: &str, ty: ResourceType) -> bool {
+        ContentBlocker::new(list).should_block(url, PAGE, ty)
+    }
+
+    fn blocks_script(list: &str, url: &str) -> bool {
+        blocks(list, url, kResourceScript)
+    }
+
+    #[test]
+    fn parse_skips_unsupported_lines() {
+        let blocker = ContentBlocker::new(
+            "[Adblock Plus 2.0]\n\
+             ! comment\n\
+             \n\
+             example.com##.ad\n\
+             example.com#@#.ad\n\
+             /ads?[0-9]/\n\
+             /ads/\n\
+             ||example.com^$rewrite=abp-resource:blank-js\n\
+             ||example.com^\n",
+        );
+
+        assert_eq!(blocker.len(), 1);
+        assert!(ContentBlocker::new("").is_empty());
+    }
+
+    #[test]
+    fn domain_anchor() {
+        let list = "||ads.example.com^";
+
+        assert!(blocks_script(list, "https://ads.example.com/a.js"));
+        assert!(blocks_script(list, "http://cdn.ads.example.com/a.js"));
+        assert!(blocks_script(
+            list,
+            "https://user@ads.example.com:8080/a.js"
+        ));
+        assert!(!blocks_script(list, "https://badads.example.com/a.js"));
+        assert!(!blocks_script(list, "https://ads.example.community/a.js"));
+        assert!(!blocks_script(
+            list,
+            "https://example.com/?u=ads.example.com/"
+        ));
+    }
+
+    #[test]
+    fn start_and_end_anchors() {
+        assert!(blocks_script(
+            "|https://ads.",
+            "https://ads.example.com/a.js"
+        ));
+        assert!(!blocks_script(
+            "|https://ads.",
+            "http://x.com/https://ads.js"
+        ));
+
+        assert!(blocks_script("/ad.js|", "https://example.com/ad.js"));
+        assert!(!blocks_script("/ad.js|", "https://example.com/ad.js?v=1"));
+
+        assert!(blocks_script(
+            "|https://example.com/ad.js|",
+            "https://example.com/ad.js"
+        ));
+        assert!(!blocks_script(
+            "|https://example.com/ad.js|",
+            "https://example.com/ad.json"
+        ));
+    }
+
+    #[test]
+    fn separator() {
+        let list = "||example.com^ads^";
+
+        assert!(blocks_script(list, "https://example.com/ads/a.js"));
+        assert!(blocks_script(list, "https://example.com/ads?a=1"));
+        assert!(blocks_script(list, "https://example.com/ads"));
+        assert!(!blocks_script(list, "https://example.com/ads.js"));
+        assert!(!blocks_script(list, "https://example.com/ads-a.js"));
+        assert!(!blocks_script(list, "https://example.com/adsense/a.js"));
+    }
+
+    #[test]
+    fn wildcard() {
+        let list = "/banner/*/img^";
+
+        assert!(blocks_script(list, "https://example.com/banner/foo/img"));
+        assert!(blocks_script(
+            list,
+            "https://example.com/banner/foo/bar/img?x"
+        ));
+        assert!(blocks_script(list, "https://example.com/banner//img"));
+        assert!(!blocks_script(list, "https://example.com/banner/img"));
+        assert!(!blocks_script(list, "https://example.com/banner/foo/imgs"));
+
+        assert!(blocks_script("*", "https://example.com/"));
+        assert!(blocks_script("a*b*c|", "https://example.com/a-b-c"));
+        assert!(!blocks_script("a*b*c|", "https://example.com/a-c-b"));
+    }
+
+    #[test]
+    fn wildcard_is_not_exponential() {
+        let url = format!("https://example.com/{}", "a".repeat(4096));
+        let list = "a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*b\n\
+                    ||example.com^*a*a*a*a*a*a*a*a*a*a*a*a*b|";
+
+        assert!(!blocks_script(list, &url));
+        assert!(blocks_script(list, &format!("{}b", url)));
+    }
+
+    #[test]
+    fn type_options() {
+        let list = "/ads/$script,image";
+
+        assert!(blocks(
+            list,
+            "https://example.com/ads/a.js",
+            kResourceScript
+        ));
+        assert!(blocks(
+            list,
+            "https://example.com/ads/a.png",
+            kResourceImage
+        ));
+        assert!(blocks(
+            list,
+            "https://example.com/ads/a.ico",
+            kResourceFavicon
+        ));
+        assert!(!blocks(
+            list,
+            "https://example.com/ads/a.css",
+            kResourceStylesheet
+        ));
+
+        let list = "/ads/$~image";
+        assert!(blocks(
+            list,
+            "https://example.com/ads/a.js",
+            kResourceScript
+        ));
+        assert!(!blocks(
+            list,
+            "https://example.com/ads/a.png",
+            kResourceImage
+        ));
+
+        // Without a type option the document itself is not blocked.
+        assert!(!blocks(
+            "/ads/",
+            "https://example.com/ads/",
+            kResourceMainFrame
+        ));
+        assert!(blocks(
+            "/ads/$document",
+            "https://example.com/ads/",
+            kResourceMainFrame
+        ));
+        assert!(blocks(
+            "/ads/$other",
+            "https://example.com/ads/",
+            kResourceWorker
+        ));
+    }
+
+    #[test]
+    fn party_options() {
+        let list = "/track.js$third-party";
+        let blocker = ContentBlocker::new(list);
+
+        assert!(blocker.should_block(
+            "https://tracker.net/track.js",
+            "https://www.example.org/",
+            kResourceScript
+        ));
+        assert!(!blocker.should_block(
+            "https://static.example.org/track.js",
+            "https://www.example.org/",
+            kResourceScript
+        ));
+
+        let blocker = ContentBlocker::new("/track.js$~third-party");
+        assert!(blocker.should_block(
+            "https://static.example.org/track.js",
+            "https://www.example.org/",
+            kResourceScript
+        ));
+        assert!(!blocker.should_block(
+            "https://tracker.net/track.js",
+            "https://www.example.org/",
+            kResourceScript
+        ));
+    }
+
+    #[test]
+    fn domain_option() {
+        let blocker = ContentBlocker::new("/ads/$domain=example.org|~shop.example.org");
+        let should_block = |source: &str| {
+            blocker.should_block("https://cdn.net/ads/a.js", source, kResourceScript)
+        };
+
+        assert!(should_block("https://example.org/"));
+        assert!(should_block("https://www.example.org/"));
+        assert!(!should_block("https://shop.example.org/"));
+        assert!(!should_block("https://example.com/"));
+    }
+
+    #[test]
+    fn match_case_option() {
+        assert!(blocks_script("/Ads/*", "https://example.com/ads/a.js"));
+        assert!(blocks_script(
+            "/Ads/*$match-case",
+            "https://example.com/Ads/a.js"
+        ));
+        assert!(!blocks_script(
+            "/Ads/*$match-case",
+            "https://example.com/ads/a.js"
+        ));
+    }
+
+    #[test]
+    fn exceptions() {
+        let list = "||example.com^\n@@||example.com/allowed/";
+
+        assert!(blocks_script(list, "https://example.com/ads.js"));
+        assert!(!blocks_script(list, "https://example.com/allowed/a.js"));
+
+        let list = "/ads/*\n@@/ads/$image";
+        assert!(blocks(
+            list,
+            "https://example.com/ads/a.js",
+            kResourceScript
+        ));
+        assert!(!blocks(
+            list,
+            "https://example.com/ads/a.png",
+            kResourceImage
+        ));
+    }
+
+    #[test]
+    fn document_exceptions() {
+        let blocker = ContentBlocker::new("/ads/*\n@@||example.org^$document");
+
+        assert!(!blocker.should_block(
+            "https://cdn.net/ads/a.js",
+            "https://example.org/",
+            kResourceScript
+        ));
+        assert!(blocker.should_block(
+            "https://cdn.net/ads/a.js",
+            "https://example.com/",
+            kResourceScript
+        ));
+    }
+
+    #[test]
+    fn token_index() {
+        let token = |line: &str| Rule::parse(line).unwrap().token();
+
+        assert_eq!(token("||example.com^").as_deref(), Some("example"));
+        assert_eq!(token("/banner/*/img^").as_deref(), Some("banner"));
+        assert_eq!(token("ads*"), None);
+        assert_eq!(token("/Ads/*$match-case"), None);
+    }
+
+    #[test]
+    fn base_domains() {
+        assert_eq!(base_domain("example.com"), "example.com");
+        assert_eq!(base_domain("www.example.com"), "example.com");
+        assert_eq!(base_domain("a.b.example.com"), "example.com");
+        assert_eq!(base_domain("localhost"), "localhost");
+        assert_eq!(base_domain(""), "");
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{Image, Position};
+
+/// The cursor a page asks the host to show.
+///
+/// The names follow the CSS `cursor` property, so they map directly to the
+/// cursor icons of windowing libraries such as winit.
+#[derive(Debug, Clone)]
+pub enum CursorType {
+    Default,
+    Crosshair,
+    /// The hand shown over links.
+    Pointer,
+    Text,
+    VerticalText,
+    Wait,
+    Progress,
+    Help,
+    Move,
+    AllScroll,
+    Cell,
+    ContextMenu,
+    Alias,
+    Copy,
+    NoDrop,
+    NotAllowed,
+    ZoomIn,
+    ZoomOut,
+    Grab,
+    Grabbing,
+    EResize,
+    NResize,
+    NeResize,
+    NwResize,
+    SResize,
+    SeResize,
+    SwResize,
+    WResize,
+    EwResize,
+    NsResize,
+    NeswResize,
+    NwseResize,
+    ColResize,
+    RowResize,
+    /// Hide the cursor.
+    None,
+    /// A cursor image set by the page, e.g. with `cursor: url(...)`.
+    Custom(CustomCursor),
+}
+
+/// A custom cursor image.
+#[derive(Debug, Clone)]
+pub struct CustomCursor {
+    pub image: Image,
+    /// The hotspot in pixels of |image|.
+    pub hotspot: Position,
+    /// The device scale factor of |image|, divide the size and hotspot by it
+    /// to get view coordinates.
+    pub scale_factor: f32,
+}
+
+pub(crate) mod wrapper {
+    use std::slice::from_raw_parts;
+
+    use webview_sys::{CursorInfo, CursorType as Type};
+
+    use super::{CursorType, CustomCursor};
+    use crate::{Image, Position};
+
+    impl CursorType {
+        pub(crate) fn new(ty: Type, custom_cursor: Option<&CursorInfo>) -> Self {
+            match ty {
+                Type::kCursorPointer => Self::Default,
+                Type::kCursorCross => Self::Crosshair,
+                Type::kCursorHand => Self::Pointer,
+                Type::kCursorIBeam => Self::Text,
+                Type::kCursorVerticalText => Self::VerticalText,
+                Type::kCursorWait => Self::Wait,
+                Type::kCursorProgress => Self::Progress,
+                Type::kCursorHelp => Self::Help,
+                Type::kCursorMove | Type::kCursorDndMove => Self::Move,
+                Type::kCursorMiddlePanning
+                | Type::kCursorMiddlePanningVertical
+                | Type::kCursorMiddlePanningHorizontal => Self::AllScroll,
+                Type::kCursorCell => Self::Cell,
+                Type::kCursorContextMenu => Self::ContextMenu,
+                Type::kCursorAlias | Type::kCursorDndLink => Self::Alias,
+                Type::kCursorCopy | Type::kCursorDndCopy => Self::Copy,
+                Type::kCursorNoDrop | Type::kCursorDndNone => Self::NoDrop,
+                Type::kCursorNotAllowed => Self::NotAllowed,
+                Type::kCursorZoomIn => Self::ZoomIn,
+                Type::kCursorZoomOut => Self::ZoomOut,
+                Type::kCursorGrab => Self::Grab,
+                Type::kCursorGrabbing => Self::Grabbing,
+                Type::kCursorEastResize | Type::kCursorEastPanning => Self::EResize,
+                Type::kCursorNorthResize | Type::kCursorNorthPanning => Self::NResize,
+                Type::kCursorNorthEastResize | Type::kCursorNorthEastPanning => Self::NeResize,
+                Type::kCursorNorthWestResize | Type::kCursorNorthWestPanning => Self::NwResize,
+                Type::kCursorSouthResize | Type::kCursorSouthPanning => Self::SResize,
+                Type::kCursorSouthEastResize | Type::kCursorSouthEastPanning => Self::SeResize,
+                Type::kCursorSouthWestResize | Type::kCursorSouthWestPanning => Self::SwResize,
+                Type::kCursorWestResize | Type::kCursorWestPanning => Self::WResize,
+                Type::kCursorEastWestResize => Self::EwResize,
+                Type::kCursorNorthSouthResize => Self::NsResize,
+                Type::kCursorNorthEastSouthWestResize => Self::NeswResize,
+                Type::kCursorNorthWestSouthEastResize => Self::NwseResize,
+                Type::kCursorColumnResize => Self::ColResize,
+                Type::kCursorRowResize => Self::RowResize,
+                Type::kCursorNone => Self::None,
+                Type::kCursorCustom => match custom_cursor {
+                    Some(info) if !info.buffer.is_null() => Self::Custom(CustomCursor::new(info)),
+                    _ => Self::Default,
+                },
+            }
+        }
+    }
+
+    impl CustomCursor {
+        fn new(info: &CursorInfo) -> Self {
+            let (width, height) = if info.buffer.is_null() {
+                (0, 0)
+            } else {
+                (info.width.max(0) as u32, info.height.max(0) as u32)
+            };
+
+            let size = width as usize * height as usize * 4;
+            let buffer = if size == 0 {
+                &[]
+            } else {
+                unsafe { from_raw_parts(info.buffer as _, size) }
+            };
+
+            Self {
+                image: Image::from_bgra(buffer, width, height),
+                hotspot: Position {
+                    x: info.hotspot_x,
+                    y: info.hotspot_y,
+                },
+                scale_factor: info.image_scale_factor,
+            }
+        }
+    }
+}
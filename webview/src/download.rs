@@ -0,0 +1,137 @@
+use std::ffi::c_void;
+
+use webview_sys::{
+    before_download_callback_cancel, before_download_callback_continue, download_callback_cancel,
+    download_callback_pause, download_callback_release, download_callback_resume, DownloadState,
+};
+
+use crate::ffi;
+
+/// Information about a download.
+#[derive(Debug, Clone)]
+pub struct DownloadItem {
+    /// The unique identifier for this download.
+    pub id: u32,
+    /// The URL, it may differ from |original_url| after redirects.
+    pub url: String,
+    /// The original URL before any redirections.
+    pub original_url: String,
+    /// The suggested file name.
+    pub suggested_file_name: String,
+    /// The full path to the downloaded or downloading file, empty until the
+    /// destination is chosen.
+    pub full_path: String,
+    /// The mime type.
+    pub mime_type: String,
+    /// The number of received bytes.
+    pub received_bytes: i64,
+    /// The total number of bytes, zero or negative if unknown.
+    pub total_bytes: i64,
+    /// A simple speed estimate in bytes/s.
+    pub current_speed: i64,
+    /// The rough percent complete or -1 if the receive total size is unknown.
+    pub percent_complete: i32,
+    pub state: DownloadState,
+}
+
+/// Callback interface used to asynchronously continue a download.
+///
+/// The callback can be answered from any thread. Dropping the callback
+/// without answering rejects the download.
+pub struct BeforeDownloadCallback(*mut c_void);
+
+unsafe impl Send for BeforeDownloadCallback {}
+unsafe impl Sync for BeforeDownloadCallback {}
+
+impl BeforeDownloadCallback {
+    pub(crate) fn new(raw: *mut c_void) -> Self {
+        Self(raw)
+    }
+
+    /// Accept the download.
+    ///
+    /// Set |path| to the full file path for the download including the file
+    /// name, or to `None` to use the suggested name and the default temp
+    /// directory. Set |show_dialog| to true to show the default "Save As"
+    /// dialog.
+    pub fn proceed(mut self, path: Option<&str>, show_dialog: bool) {
+        let path = ffi::into_opt(path);
+
+        unsafe { before_download_callback_continue(self.0, path, show_dialog) }
+        self.0 = std::ptr::null_mut();
+
+        ffi::free(path);
+    }
+
+    /// Reject the download.
+    pub fn cancel(self) {
+        drop(self)
+    }
+}
+
+impl Drop for BeforeDownloadCallback {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { before_download_callback_cancel(self.0) }
+        }
+    }
+}
+
+/// Handle used to control a download in progress.
+///
+/// The handle can be kept and used from any thread until the download
+/// completes.
+pub struct DownloadHandle(*mut c_void);
+
+unsafe impl Send for DownloadHandle {}
+unsafe impl Sync for DownloadHandle {}
+
+impl DownloadHandle {
+    pub(crate) fn new(raw: *mut c_void) -> Self {
+        Self(raw)
+    }
+
+    /// Cancel the download.
+    pub fn cancel(&self) {
+        unsafe { download_callback_cancel(self.0) }
+    }
+
+    /// Pause the download.
+    pub fn pause(&self) {
+        unsafe { download_callback_pause(self.0) }
+    }
+
+    /// Resume the download.
+    pub fn resume(&self) {
+        unsafe { download_callback_resume(self.0) }
+    }
+}
+
+impl Drop for DownloadHandle {
+    fn drop(&mut self) {
+        unsafe { download_callback_release(self.0) }
+    }
+}
+
+pub(crate) mod wrapper {
+    use super::DownloadItem;
+    use crate::ffi;
+
+    impl From<&webview_sys::DownloadItem> for DownloadItem {
+        fn from(item: &webview_sys::DownloadItem) -> Self {
+            Self {
+                id: item.id,
+                url: ffi::from(item.url).unwrap_or_default(),
+                original_url: ffi::from(item.original_url).unwrap_or_default(),
+                suggested_file_name: ffi::from(item.suggested_file_name).unwrap_or_default(),
+                full_path: ffi::from(item.full_path).unwrap_or_default(),
+                mime_type: ffi::from(item.mime_type).unwrap_or_default(),
+                received_bytes: item.received_bytes,
+                total_bytes: item.total_bytes,
+                current_speed: item.current_speed,
+                percent_complete: item.percent_complete,
+                state: item.state,
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use bitflags::bitflags;
+
+use crate::{Image, Position};
+
+bitflags! {
+    /// Drag operations, mirrors cef_drag_operations_mask_t.
+    ///
+    /// An empty set means no operation, e.g. a cancelled drop.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+    pub struct DragOperations: u32 {
+        const COPY = 1;
+        const LINK = 2;
+        const GENERIC = 4;
+        const PRIVATE = 8;
+        const MOVE = 16;
+        const DELETE = 32;
+    }
+}
+
+/// The data of a drag operation.
+#[derive(Debug, Clone, Default)]
+pub struct DragData {
+    /// The url being dragged.
+    pub link_url: Option<String>,
+    /// The title associated with |link_url|.
+    pub link_title: Option<String>,
+    /// The plain text fragment being dragged.
+    pub text: Option<String>,
+    /// The HTML fragment being dragged.
+    pub html: Option<String>,
+    /// The base url that |html| is associated with, used for resolving
+    /// relative urls.
+    pub html_base_url: Option<String>,
+    /// The full paths of the files being dragged.
+    pub files: Vec<String>,
+}
+
+/// The image shown under the cursor while dragging.
+#[derive(Debug, Clone)]
+pub struct DragImage {
+    pub image: Image,
+    /// The position of the cursor in pixels of |image|.
+    pub hotspot: Position,
+}
+
+pub(crate) mod wrapper {
+    use std::{ffi::c_char, ptr::null_mut, slice::from_raw_parts};
+
+    use super::{DragData, DragImage};
+    use crate::{ffi, Image, Position};
+
+    /// A drag data of the host passed to the page.
+    pub(crate) struct DragDataWrapper {
+        pub inner: webview_sys::DragData,
+        files: Vec<*const c_char>,
+    }
+
+    impl DragDataWrapper {
+        pub(crate) fn new(data: &DragData) -> Self {
+            let files = data
+                .files
+                .iter()
+                .map(|it| ffi::into(it))
+                .collect::<Vec<_>>();
+
+            Self {
+                inner: webview_sys::DragData {
+                    link_url: ffi::into_opt(data.link_url.as_deref()),
+                    link_title: ffi::into_opt(data.link_title.as_deref()),
+                    fragment_text: ffi::into_opt(data.text.as_deref()),
+                    fragment_html: ffi::into_opt(data.html.as_deref()),
+                    fragment_base_url: ffi::into_opt(data.html_base_url.as_deref()),
+                    file_paths: if files.is_empty() {
+                        null_mut()
+                    } else {
+                        files.as_ptr() as _
+                    },
+                    file_paths_size: files.len(),
+                },
+                files,
+            }
+        }
+    }
+
+    impl Drop for DragDataWrapper {
+        fn drop(&mut self) {
+            ffi::free(self.inner.link_url);
+            ffi::free(self.inner.link_title);
+            ffi::free(self.inner.fragment_text);
+            ffi::free(self.inner.fragment_html);
+            ffi::free(self.inner.fragment_base_url);
+
+            for it in &self.files {
+                ffi::free(*it);
+            }
+        }
+    }
+
+    impl From<&webview_sys::DragData> for DragData {
+        fn from(data: &webview_sys::DragData) -> Self {
+            let files = if data.file_paths.is_null() {
+                &[]
+            } else {
+                unsafe { from_raw_parts(data.file_paths, data.file_paths_size) }
+            };
+
+            Self {
+                link_url: ffi::from(data.link_url),
+                link_title: ffi::from(data.link_title),
+                text: ffi::from(data.fragment_text),
+                html: ffi::from(data.fragment_html),
+                html_base_url: ffi::from(data.fragment_base_url),
+                files: files.iter().filter_map(|it| ffi::from(*it)).collect(),
+            }
+        }
+    }
+
+    impl From<&webview_sys::DragImage> for DragImage {
+        fn from(image: &webview_sys::DragImage) -> Self {
+            // The buffer of an empty bitmap is null.
+            let (width, height) = if image.buffer.is_null() {
+                (0, 0)
+            } else {
+                (image.width.max(0) as u32, image.height.max(0) as u32)
+            };
+
+            let size = width as usize * height as usize * 4;
+            let buffer = if size == 0 {
+                &[]
+            } else {
+                unsafe { from_raw_parts(image.buffer as _, size) }
+            };
+
+            Self {
+                image: Image::from_bgra(buffer, width, height),
+                hotspot: Position {
+                    x: image.hotspot_x,
+                    y: image.hotspot_y,
+                },
+            }
+        }
+    }
+}
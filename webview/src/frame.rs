@@ -0,0 +1,509 @@
+use std::time::Instant;
+
+use webview_sys::Rect;
+
+/// The layout of the pixels of a frame.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PixelFormat {
+    /// 4 bytes per pixel in B, G, R, A order.
+    Bgra,
+    /// 4 bytes per pixel in R, G, B, A order.
+    Rgba,
+    /// 3 bytes per pixel in R, G, B order.
+    Rgb,
+}
+
+impl PixelFormat {
+    /// The number of bytes of a pixel.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Bgra | Self::Rgba => 4,
+            Self::Rgb => 3,
+        }
+    }
+}
+
+/// How the color channels relate to the alpha channel.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AlphaMode {
+    /// The color channels are already multiplied by alpha, this is what
+    /// chromium paints.
+    Premultiplied,
+    /// The color channels are independent of alpha.
+    Straight,
+}
+
+/// A frame painted by the page.
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'a> {
+    /// The pixel data, |stride| * |height| bytes with an upper-left origin.
+    pub buffer: &'a [u8],
+    /// The width in pixels.
+    pub width: u32,
+    /// The height in pixels.
+    pub height: u32,
+    /// The number of bytes of a row, it may be larger than |width| times the
+    /// bytes of a pixel.
+    pub stride: u32,
+    /// Always `PixelFormat::Bgra` for frames of `Observer::on_frame`.
+    pub format: PixelFormat,
+    /// Always `AlphaMode::Premultiplied` for frames of `Observer::on_frame`.
+    pub alpha: AlphaMode,
+    /// The device scale factor the frame was painted with, |width| and
+    /// |height| are view coordinates times this factor.
+    pub device_scale_factor: f32,
+    /// When the frame was received from chromium.
+    pub timestamp: Instant,
+    /// The sequence number of the frame, starting at zero for the first frame
+    /// of the page.
+    pub sequence: u64,
+    /// With `PageOptions::external_begin_frame`, the id returned by the last
+    /// `Page::send_begin_frame` that reached chromium before the frame was
+    /// painted.
+    ///
+    /// A begin frame paints at most one frame, and none if nothing changed,
+    /// so the frame answers the begin frame of this id as long as the next
+    /// one is sent after the frame arrived.
+    pub begin_frame: Option<u64>,
+    /// The rectangles in pixel coordinates that changed since the previous
+    /// frame.
+    pub dirty_rects: &'a [Rect],
+}
+
+impl<'a> Frame<'a> {
+    /// The pixel data of the row at |y| without the padding of the stride.
+    pub fn row(&self, y: u32) -> &'a [u8] {
+        let start = y as usize * self.stride as usize;
+        &self.buffer[start..start + self.width as usize * self.format.bytes_per_pixel()]
+    }
+
+    /// Iterate over the rows of the frame, see `Frame::row`.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// Convert the frame to tightly packed RGBA with the given alpha mode.
+    pub fn to_rgba(&self, alpha: AlphaMode) -> Vec<u8> {
+        self.convert(PixelFormat::Rgba, alpha)
+    }
+
+    /// Convert the frame to tightly packed BGRA with the given alpha mode.
+    pub fn to_bgra(&self, alpha: AlphaMode) -> Vec<u8> {
+        self.convert(PixelFormat::Bgra, alpha)
+    }
+
+    /// Convert the frame to tightly packed RGB, the frame is composited over
+    /// black.
+    pub fn to_rgb(&self) -> Vec<u8> {
+        self.convert(PixelFormat::Rgb, AlphaMode::Premultiplied)
+    }
+
+    /// Convert the frame to one `0xAARRGGBB` value per pixel with straight
+    /// alpha, the layout expected by most software window buffers.
+    pub fn to_argb32(&self) -> Vec<u32> {
+        let mut pixels = Vec::with_capacity(self.width as usize * self.height as usize);
+        for row in self.rows() {
+            for pixel in row.chunks_exact(self.format.bytes_per_pixel()) {
+                let [r, g, b, a] = self.read(pixel, AlphaMode::Straight);
+                pixels.push(u32::from_be_bytes([a, r, g, b]));
+            }
+        }
+
+        pixels
+    }
+
+    fn convert(&self, format: PixelFormat, alpha: AlphaMode) -> Vec<u8> {
+        let mut output = Vec::with_capacity(
+            self.width as usize * self.height as usize * format.bytes_per_pixel(),
+        );
+
+        for row in self.rows() {
+            for pixel in row.chunks_exact(self.format.bytes_per_pixel()) {
+                let [r, g, b, a] = self.read(pixel, alpha);
+                match format {
+                    PixelFormat::Bgra => output.extend_from_slice(&[b, g, r, a]),
+                    PixelFormat::Rgba => output.extend_from_slice(&[r, g, b, a]),
+                    PixelFormat::Rgb => output.extend_from_slice(&[r, g, b]),
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Read a pixel as RGBA in the given alpha mode.
+    fn read(&self, pixel: &[u8], alpha: AlphaMode) -> [u8; 4] {
+        let [r, g, b, a] = match self.format {
+            PixelFormat::Bgra => [pixel[2], pixel[1], pixel[0], pixel[3]],
+            PixelFormat::Rgba => [pixel[0], pixel[1], pixel[2], pixel[3]],
+            PixelFormat::Rgb => [pixel[0], pixel[1], pixel[2], 255],
+        };
+
+        match (self.alpha, alpha) {
+            (AlphaMode::Premultiplied, AlphaMode::Straight) => [
+                unpremultiply(r, a),
+                unpremultiply(g, a),
+                unpremultiply(b, a),
+                a,
+            ],
+            (AlphaMode::Straight, AlphaMode::Premultiplied) => {
+                [premultiply(r, a), premultiply(g, a), premultiply(b, a), a]
+            }
+            _ => [r, g, b, a],
+        }
+    }
+}
+
+fn premultiply(value: u8, alpha: u8) -> u8 {
+    ((value as u32 * alpha as u32 + 127) / 255) as u8
+}
+
+fn unpremultiply(value: u8, alpha: u8) -> u8 {
+    if alpha == 0 {
+        0
+    } else {
+        ((value as u32 * 255 + alpha as u32 / 2) / alpha as u32).min(255) as u8
+    }
+}
+
+/// Copy only the dirty regions of a frame into a caller-owned buffer.
+///
+/// |dst| is a buffer with the same layout as the frame holding the previous
+/// frame, so after the copy |dst| equals the frame buffer while only the
+/// changed pixels were touched. Rects are clamped to the frame.
+pub fn copy_dirty_rects(frame: &Frame, dst: &mut [u8]) {
+    let stride = frame.stride as usize;
+    let bytes_per_pixel = frame.format.bytes_per_pixel();
+    let height = (frame.height as usize).min(dst.len().checked_div(stride).unwrap_or(0));
+
+    for rect in frame.dirty_rects {
+        let x = (rect.x.max(0) as usize).min(frame.width as usize);
+        let y = (rect.y.max(0) as usize).min(height);
+        let right = (rect.x.saturating_add(rect.width).max(0) as usize).min(frame.width as usize);
+        let bottom = (rect.y.saturating_add(rect.height).max(0) as usize).min(height);
+
+        if right <= x {
+            continue;
+        }
+
+        for row in y..bottom {
+            let start = row * stride + x * bytes_per_pixel;
+            let end = row * stride + right * bytes_per_pixel;
+            dst[start..end].copy_from_slice(&frame.buffer[start..end]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use webview_sys::Rect;
+
+    use super::{copy_dirty_rects, premultiply, unpremultiply, AlphaMode, Frame, PixelFormat};
+    use crate::Image;
+
+    /// Padding bytes at the end of every row, they must never show up in the
+    /// output.
+    const PAD: u8 = 0xEE;
+
+    fn frame<'a>(
+        buffer: &'a [u8],
+        width: u32,
+        stride: u32,
+        format: PixelFormat,
+        alpha: AlphaMode,
+        dirty_rects: &'a [Rect],
+    ) -> Frame<'a> {
+        Frame {
+            buffer,
+            width,
+            height: buffer.len() as u32 / stride,
+            stride,
+            format,
+            alpha,
+            device_scale_factor: 1.0,
+            timestamp: Instant::now(),
+            sequence: 0,
+            begin_frame: None,
+            dirty_rects,
+        }
+    }
+
+    /// A 2x2 premultiplied BGRA frame with 4 bytes of padding per row: opaque
+    /// red, half transparent green, fully transparent and opaque white.
+    #[rustfmt::skip]
+    const BGRA: [u8; 24] = [
+        0, 0, 255, 255,   0, 128, 0, 128,     PAD, PAD, PAD, PAD,
+        0, 0, 0, 0,       255, 255, 255, 255, PAD, PAD, PAD, PAD,
+    ];
+
+    #[test]
+    fn premultiply_edges() {
+        for value in 0..=255 {
+            assert_eq!(premultiply(value, 0), 0);
+            assert_eq!(premultiply(value, 255), value);
+            assert_eq!(unpremultiply(value, 0), 0);
+            assert_eq!(unpremultiply(value, 255), value);
+        }
+
+        assert_eq!(premultiply(255, 128), 128);
+        assert_eq!(unpremultiply(128, 128), 255);
+
+        // Invalid premultiplied values larger than alpha are clamped.
+        assert_eq!(unpremultiply(200, 100), 255);
+    }
+
+    #[test]
+    fn premultiply_round_trip() {
+        for alpha in 1..=255 {
+            for value in 0..=alpha {
+                assert_eq!(premultiply(unpremultiply(value, alpha), alpha), value);
+            }
+        }
+    }
+
+    #[test]
+    fn rows_skip_stride_padding() {
+        let frame = frame(
+            &BGRA,
+            2,
+            12,
+            PixelFormat::Bgra,
+            AlphaMode::Premultiplied,
+            &[],
+        );
+
+        assert_eq!(frame.height, 2);
+        assert_eq!(frame.row(1), &BGRA[12..20]);
+        assert!(frame.rows().all(|it| it.len() == 8 && !it.contains(&PAD)));
+    }
+
+    #[test]
+    fn convert_premultiplied() {
+        let frame = frame(
+            &BGRA,
+            2,
+            12,
+            PixelFormat::Bgra,
+            AlphaMode::Premultiplied,
+            &[],
+        );
+
+        assert_eq!(
+            frame.to_bgra(AlphaMode::Premultiplied),
+            [BGRA[0..8].to_vec(), BGRA[12..20].to_vec()].concat()
+        );
+
+        #[rustfmt::skip]
+        assert_eq!(
+            frame.to_rgba(AlphaMode::Premultiplied),
+            [
+                255, 0, 0, 255,  0, 128, 0, 128,
+                0, 0, 0, 0,      255, 255, 255, 255,
+            ]
+        );
+
+        #[rustfmt::skip]
+        assert_eq!(
+            frame.to_rgba(AlphaMode::Straight),
+            [
+                255, 0, 0, 255,  0, 255, 0, 128,
+                0, 0, 0, 0,      255, 255, 255, 255,
+            ]
+        );
+
+        #[rustfmt::skip]
+        assert_eq!(
+            frame.to_bgra(AlphaMode::Straight),
+            [
+                0, 0, 255, 255,  0, 255, 0, 128,
+                0, 0, 0, 0,      255, 255, 255, 255,
+            ]
+        );
+
+        // Premultiplied colors are the colors composited over black.
+        #[rustfmt::skip]
+        assert_eq!(
+            frame.to_rgb(),
+            [
+                255, 0, 0,  0, 128, 0,
+                0, 0, 0,    255, 255, 255,
+            ]
+        );
+
+        assert_eq!(
+            frame.to_argb32(),
+            [0xFFFF0000, 0x8000FF00, 0x00000000, 0xFFFFFFFF]
+        );
+    }
+
+    #[test]
+    fn convert_straight_round_trip() {
+        #[rustfmt::skip]
+        let rgba = [
+            10, 20, 30, 255,  40, 50, 60, 0,  PAD, PAD,
+            70, 80, 90, 51,   255, 0, 0, 1,   PAD, PAD,
+        ];
+
+        let straight = frame(&rgba, 2, 10, PixelFormat::Rgba, AlphaMode::Straight, &[]);
+        let premultiplied = straight.to_rgba(AlphaMode::Premultiplied);
+
+        // Fully transparent pixels lose their color.
+        #[rustfmt::skip]
+        assert_eq!(
+            premultiplied,
+            [
+                10, 20, 30, 255,  0, 0, 0, 0,
+                14, 16, 18, 51,   1, 0, 0, 1,
+            ]
+        );
+
+        let frame = frame(
+            &premultiplied,
+            2,
+            8,
+            PixelFormat::Rgba,
+            AlphaMode::Premultiplied,
+            &[],
+        );
+
+        #[rustfmt::skip]
+        assert_eq!(
+            frame.to_rgba(AlphaMode::Straight),
+            [
+                10, 20, 30, 255,  0, 0, 0, 0,
+                70, 80, 90, 51,   255, 0, 0, 1,
+            ]
+        );
+    }
+
+    #[test]
+    fn convert_rgb() {
+        let rgb = [1, 2, 3, 4, 5, 6, PAD, PAD];
+        let frame = frame(&rgb, 2, 8, PixelFormat::Rgb, AlphaMode::Straight, &[]);
+
+        assert_eq!(frame.to_rgb(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            frame.to_rgba(AlphaMode::Straight),
+            [1, 2, 3, 255, 4, 5, 6, 255]
+        );
+        assert_eq!(frame.to_argb32(), [0xFF010203, 0xFF040506]);
+    }
+
+    #[test]
+    fn copy_dirty_rects_only_touches_dirty_pixels() {
+        let rects = [Rect {
+            x: 1,
+            y: 1,
+            width: 1,
+            height: 1,
+        }];
+
+        let frame = frame(
+            &BGRA,
+            2,
+            12,
+            PixelFormat::Bgra,
+            AlphaMode::Premultiplied,
+            &rects,
+        );
+        let mut dst = [0u8; 24];
+        copy_dirty_rects(&frame, &mut dst);
+
+        let mut expected = [0u8; 24];
+        expected[16..20].copy_from_slice(&BGRA[16..20]);
+        assert_eq!(dst, expected);
+    }
+
+    #[test]
+    fn copy_dirty_rects_clamps_bogus_rects() {
+        let rects = [
+            Rect {
+                x: i32::MAX,
+                y: i32::MAX,
+                width: i32::MAX,
+                height: i32::MAX,
+            },
+            Rect {
+                x: 1,
+                y: 1,
+                width: i32::MAX,
+                height: i32::MAX,
+            },
+            Rect {
+                x: -10,
+                y: -10,
+                width: 11,
+                height: 11,
+            },
+            Rect {
+                x: 0,
+                y: 0,
+                width: -5,
+                height: 1,
+            },
+        ];
+
+        let frame = frame(
+            &BGRA,
+            2,
+            12,
+            PixelFormat::Bgra,
+            AlphaMode::Premultiplied,
+            &rects,
+        );
+        let mut dst = [0u8; 24];
+        copy_dirty_rects(&frame, &mut dst);
+
+        let mut expected = [0u8; 24];
+        expected[0..4].copy_from_slice(&BGRA[0..4]);
+        expected[16..20].copy_from_slice(&BGRA[16..20]);
+        assert_eq!(dst, expected);
+
+        // A destination smaller than the frame is only filled as far as it
+        // goes.
+        let mut dst = [0u8; 12];
+        copy_dirty_rects(&frame, &mut dst);
+        assert_eq!(dst, expected[0..12]);
+    }
+
+    #[test]
+    fn capture_clips_to_the_frame() {
+        let frame = frame(
+            &BGRA,
+            2,
+            12,
+            PixelFormat::Bgra,
+            AlphaMode::Premultiplied,
+            &[],
+        );
+
+        let capture = |x, y, width, height| {
+            let image = Image::from_frame(
+                &frame,
+                Some(Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                }),
+            );
+
+            (image.width, image.height, image.data)
+        };
+
+        assert_eq!(capture(-1, -1, 2, 2), (1, 1, vec![255, 0, 0, 255]));
+        assert_eq!(capture(1, 1, 100, 100), (1, 1, vec![255, 255, 255, 255]));
+        assert_eq!(capture(5, 5, 1, 1), (0, 0, vec![]));
+        assert_eq!(
+            capture(i32::MIN, i32::MIN, i32::MAX, i32::MAX),
+            (0, 0, vec![])
+        );
+
+        // Negative sizes end the clip before it starts.
+        assert_eq!(capture(2, 0, -1, 2), (0, 2, vec![]));
+        assert_eq!(capture(0, 2, 2, -2), (2, 0, vec![]));
+        assert_eq!(capture(1, 1, -5, -5), (0, 0, vec![]));
+    }
+}
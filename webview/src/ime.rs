@@ -0,0 +1,112 @@
+use std::ops::Range;
+
+/// An IME composition operation.
+///
+/// The ranges are in UTF-16 code units of the text.
+#[derive(Debug, Clone)]
+pub enum ImeAction<'a> {
+    /// Begins a new composition or updates the existing composition.
+    ///
+    /// |underlines| are drawn under the given ranges of |text|, e.g. to mark
+    /// the converted clause. |replacement_range| is an optional range of the
+    /// existing text that will be replaced, it is only used on macOS.
+    /// |selection_range| is an optional range of |text| that is selected after
+    /// the update, an empty range places the cursor.
+    SetComposition {
+        text: &'a str,
+        underlines: &'a [ImeUnderline],
+        replacement_range: Option<Range<u32>>,
+        selection_range: Option<Range<u32>>,
+    },
+    /// Completes the existing composition by inserting |text| into the
+    /// composition node, or inserts |text| at the cursor without a
+    /// composition.
+    ///
+    /// |relative_cursor_position| is where the cursor will be positioned
+    /// relative to the current cursor position.
+    Commit {
+        text: &'a str,
+        replacement_range: Option<Range<u32>>,
+        relative_cursor_position: i32,
+    },
+    /// Completes the existing composition by applying the current composition
+    /// node contents. If |keep_selection| is false the current selection, if
+    /// any, will be discarded.
+    Finish { keep_selection: bool },
+    /// Cancels the existing composition and discards the composition node
+    /// contents without applying them.
+    Cancel,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImeUnderlineStyle {
+    #[default]
+    Solid,
+    Dot,
+    Dash,
+    None,
+}
+
+/// An underline of a range of the composition text.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImeUnderline {
+    pub range: Range<u32>,
+    /// ARGB, the color of the underline.
+    pub color: u32,
+    /// ARGB, the background color of the range, transparent for none.
+    pub background_color: u32,
+    /// Draw a thick underline, e.g. for the clause being converted.
+    pub thick: bool,
+    pub style: ImeUnderlineStyle,
+}
+
+impl ImeUnderline {
+    /// A thin solid black underline without background.
+    pub fn new(range: Range<u32>) -> Self {
+        Self {
+            range,
+            color: 0xFF000000,
+            background_color: 0,
+            thick: false,
+            style: ImeUnderlineStyle::Solid,
+        }
+    }
+}
+
+pub(crate) mod wrapper {
+    use std::ops::Range;
+
+    use webview_sys::{ImeRange, ImeUnderlineStyle as Style};
+
+    use super::{ImeUnderline, ImeUnderlineStyle};
+
+    pub(crate) fn to_range(range: &Range<u32>) -> ImeRange {
+        ImeRange {
+            from: range.start,
+            to: range.end,
+        }
+    }
+
+    pub(crate) fn from_range(range: ImeRange) -> Range<u32> {
+        range.from..range.to
+    }
+
+    impl ImeUnderline {
+        pub(crate) fn to_raw(&self) -> webview_sys::ImeUnderline {
+            webview_sys::ImeUnderline {
+                range: to_range(&self.range),
+                color: self.color,
+                background_color: self.background_color,
+                thick: self.thick,
+                style: match self.style {
+                    ImeUnderlineStyle::Solid => Style::kImeUnderlineSolid,
+                    ImeUnderlineStyle::Dot => Style::kImeUnderlineDot,
+                    ImeUnderlineStyle::Dash => Style::kImeUnderlineDash,
+                    ImeUnderlineStyle::None => Style::kImeUnderlineNone,
+                },
+            }
+        }
+    }
+}
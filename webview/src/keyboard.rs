@@ -0,0 +1,587 @@
+use webview_sys::KeyEventType;
+
+use crate::{ActionState, Modifiers};
+
+macro_rules! keymap {
+    ($($(#[$meta:meta])* $code:ident => $vk:expr, $scan:expr, $xkb:expr, $mac:expr, $text:expr;)*) => {
+        /// A physical key, named after the `code` values of the W3C UI Events
+        /// specification, so it does not depend on the keyboard layout.
+        #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum KeyCode {
+            $($(#[$meta])* $code,)*
+        }
+
+        impl KeyCode {
+            const ALL: &'static [Self] = &[$(Self::$code,)*];
+
+            /// The windows key code, the scan code, the xkb keycode, the macOS
+            /// key code and the lower and upper case text of a US layout.
+            fn keymap(self) -> (u32, u32, u32, u32, Option<(char, char)>) {
+                match self {
+                    $(Self::$code => ($vk, $scan, $xkb, $mac, $text),)*
+                }
+            }
+        }
+    };
+}
+
+// 0xFFFF is a key without a macOS key code.
+keymap! {
+    Escape => 0x1B, 0x0001, 0x0009, 0x0035, None;
+    Backquote => 0xC0, 0x0029, 0x0031, 0x0032, Some(('`', '~'));
+    Digit1 => 0x31, 0x0002, 0x000A, 0x0012, Some(('1', '!'));
+    Digit2 => 0x32, 0x0003, 0x000B, 0x0013, Some(('2', '@'));
+    Digit3 => 0x33, 0x0004, 0x000C, 0x0014, Some(('3', '#'));
+    Digit4 => 0x34, 0x0005, 0x000D, 0x0015, Some(('4', '$'));
+    Digit5 => 0x35, 0x0006, 0x000E, 0x0017, Some(('5', '%'));
+    Digit6 => 0x36, 0x0007, 0x000F, 0x0016, Some(('6', '^'));
+    Digit7 => 0x37, 0x0008, 0x0010, 0x001A, Some(('7', '&'));
+    Digit8 => 0x38, 0x0009, 0x0011, 0x001C, Some(('8', '*'));
+    Digit9 => 0x39, 0x000A, 0x0012, 0x0019, Some(('9', '('));
+    Digit0 => 0x30, 0x000B, 0x0013, 0x001D, Some(('0', ')'));
+    Minus => 0xBD, 0x000C, 0x0014, 0x001B, Some(('-', '_'));
+    Equal => 0xBB, 0x000D, 0x0015, 0x0018, Some(('=', '+'));
+    Backspace => 0x08, 0x000E, 0x0016, 0x0033, None;
+    Tab => 0x09, 0x000F, 0x0017, 0x0030, None;
+    KeyA => 0x41, 0x001E, 0x0026, 0x0000, Some(('a', 'A'));
+    KeyB => 0x42, 0x0030, 0x0038, 0x000B, Some(('b', 'B'));
+    KeyC => 0x43, 0x002E, 0x0036, 0x0008, Some(('c', 'C'));
+    KeyD => 0x44, 0x0020, 0x0028, 0x0002, Some(('d', 'D'));
+    KeyE => 0x45, 0x0012, 0x001A, 0x000E, Some(('e', 'E'));
+    KeyF => 0x46, 0x0021, 0x0029, 0x0003, Some(('f', 'F'));
+    KeyG => 0x47, 0x0022, 0x002A, 0x0005, Some(('g', 'G'));
+    KeyH => 0x48, 0x0023, 0x002B, 0x0004, Some(('h', 'H'));
+    KeyI => 0x49, 0x0017, 0x001F, 0x0022, Some(('i', 'I'));
+    KeyJ => 0x4A, 0x0024, 0x002C, 0x0026, Some(('j', 'J'));
+    KeyK => 0x4B, 0x0025, 0x002D, 0x0028, Some(('k', 'K'));
+    KeyL => 0x4C, 0x0026, 0x002E, 0x0025, Some(('l', 'L'));
+    KeyM => 0x4D, 0x0032, 0x003A, 0x002E, Some(('m', 'M'));
+    KeyN => 0x4E, 0x0031, 0x0039, 0x002D, Some(('n', 'N'));
+    KeyO => 0x4F, 0x0018, 0x0020, 0x001F, Some(('o', 'O'));
+    KeyP => 0x50, 0x0019, 0x0021, 0x0023, Some(('p', 'P'));
+    KeyQ => 0x51, 0x0010, 0x0018, 0x000C, Some(('q', 'Q'));
+    KeyR => 0x52, 0x0013, 0x001B, 0x000F, Some(('r', 'R'));
+    KeyS => 0x53, 0x001F, 0x0027, 0x0001, Some(('s', 'S'));
+    KeyT => 0x54, 0x0014, 0x001C, 0x0011, Some(('t', 'T'));
+    KeyU => 0x55, 0x0016, 0x001E, 0x0020, Some(('u', 'U'));
+    KeyV => 0x56, 0x002F, 0x0037, 0x0009, Some(('v', 'V'));
+    KeyW => 0x57, 0x0011, 0x0019, 0x000D, Some(('w', 'W'));
+    KeyX => 0x58, 0x002D, 0x0035, 0x0007, Some(('x', 'X'));
+    KeyY => 0x59, 0x0015, 0x001D, 0x0010, Some(('y', 'Y'));
+    KeyZ => 0x5A, 0x002C, 0x0034, 0x0006, Some(('z', 'Z'));
+    BracketLeft => 0xDB, 0x001A, 0x0022, 0x0021, Some(('[', '{'));
+    BracketRight => 0xDD, 0x001B, 0x0023, 0x001E, Some((']', '}'));
+    Backslash => 0xDC, 0x002B, 0x0033, 0x002A, Some(('\\', '|'));
+    /// The key between the left shift and `KeyZ` of ISO keyboards.
+    IntlBackslash => 0xE2, 0x0056, 0x005E, 0x000A, Some(('\\', '|'));
+    CapsLock => 0x14, 0x003A, 0x0042, 0x0039, None;
+    Semicolon => 0xBA, 0x0027, 0x002F, 0x0029, Some((';', ':'));
+    Quote => 0xDE, 0x0028, 0x0030, 0x0027, Some(('\'', '"'));
+    Enter => 0x0D, 0x001C, 0x0024, 0x0024, Some(('\r', '\r'));
+    Comma => 0xBC, 0x0033, 0x003B, 0x002B, Some((',', '<'));
+    Period => 0xBE, 0x0034, 0x003C, 0x002F, Some(('.', '>'));
+    Slash => 0xBF, 0x0035, 0x003D, 0x002C, Some(('/', '?'));
+    Space => 0x20, 0x0039, 0x0041, 0x0031, Some((' ', ' '));
+    ShiftLeft => 0x10, 0x002A, 0x0032, 0x0038, None;
+    ShiftRight => 0x10, 0x0036, 0x003E, 0x003C, None;
+    ControlLeft => 0x11, 0x001D, 0x0025, 0x003B, None;
+    ControlRight => 0x11, 0xE01D, 0x0069, 0x003E, None;
+    AltLeft => 0x12, 0x0038, 0x0040, 0x003A, None;
+    AltRight => 0x12, 0xE038, 0x006C, 0x003D, None;
+    /// The left Windows, Command or Super key.
+    MetaLeft => 0x5B, 0xE05B, 0x0085, 0x0037, None;
+    /// The right Windows, Command or Super key.
+    MetaRight => 0x5C, 0xE05C, 0x0086, 0x0036, None;
+    ContextMenu => 0x5D, 0xE05D, 0x0087, 0x006E, None;
+    F1 => 0x70, 0x003B, 0x0043, 0x007A, None;
+    F2 => 0x71, 0x003C, 0x0044, 0x0078, None;
+    F3 => 0x72, 0x003D, 0x0045, 0x0063, None;
+    F4 => 0x73, 0x003E, 0x0046, 0x0076, None;
+    F5 => 0x74, 0x003F, 0x0047, 0x0060, None;
+    F6 => 0x75, 0x0040, 0x0048, 0x0061, None;
+    F7 => 0x76, 0x0041, 0x0049, 0x0062, None;
+    F8 => 0x77, 0x0042, 0x004A, 0x0064, None;
+    F9 => 0x78, 0x0043, 0x004B, 0x0065, None;
+    F10 => 0x79, 0x0044, 0x004C, 0x006D, None;
+    F11 => 0x7A, 0x0057, 0x005F, 0x0067, None;
+    F12 => 0x7B, 0x0058, 0x0060, 0x006F, None;
+    F13 => 0x7C, 0x0064, 0x00BF, 0x0069, None;
+    F14 => 0x7D, 0x0065, 0x00C0, 0x006B, None;
+    F15 => 0x7E, 0x0066, 0x00C1, 0x0071, None;
+    F16 => 0x7F, 0x0067, 0x00C2, 0x006A, None;
+    F17 => 0x80, 0x0068, 0x00C3, 0x0040, None;
+    F18 => 0x81, 0x0069, 0x00C4, 0x004F, None;
+    F19 => 0x82, 0x006A, 0x00C5, 0x0050, None;
+    F20 => 0x83, 0x006B, 0x00C6, 0x005A, None;
+    F21 => 0x84, 0x006C, 0x00C7, 0xFFFF, None;
+    F22 => 0x85, 0x006D, 0x00C8, 0xFFFF, None;
+    F23 => 0x86, 0x006E, 0x00C9, 0xFFFF, None;
+    F24 => 0x87, 0x0076, 0x00CA, 0xFFFF, None;
+    PrintScreen => 0x2C, 0xE037, 0x006B, 0xFFFF, None;
+    ScrollLock => 0x91, 0x0046, 0x004E, 0xFFFF, None;
+    Pause => 0x13, 0x0045, 0x007F, 0xFFFF, None;
+    Insert => 0x2D, 0xE052, 0x0076, 0x0072, None;
+    Delete => 0x2E, 0xE053, 0x0077, 0x0075, None;
+    Home => 0x24, 0xE047, 0x006E, 0x0073, None;
+    End => 0x23, 0xE04F, 0x0073, 0x0077, None;
+    PageUp => 0x21, 0xE049, 0x0070, 0x0074, None;
+    PageDown => 0x22, 0xE051, 0x0075, 0x0079, None;
+    ArrowUp => 0x26, 0xE048, 0x006F, 0x007E, None;
+    ArrowDown => 0x28, 0xE050, 0x0074, 0x007D, None;
+    ArrowLeft => 0x25, 0xE04B, 0x0071, 0x007B, None;
+    ArrowRight => 0x27, 0xE04D, 0x0072, 0x007C, None;
+    NumLock => 0x90, 0xE045, 0x004D, 0x0047, None;
+    Numpad0 => 0x60, 0x0052, 0x005A, 0x0052, Some(('0', '0'));
+    Numpad1 => 0x61, 0x004F, 0x0057, 0x0053, Some(('1', '1'));
+    Numpad2 => 0x62, 0x0050, 0x0058, 0x0054, Some(('2', '2'));
+    Numpad3 => 0x63, 0x0051, 0x0059, 0x0055, Some(('3', '3'));
+    Numpad4 => 0x64, 0x004B, 0x0053, 0x0056, Some(('4', '4'));
+    Numpad5 => 0x65, 0x004C, 0x0054, 0x0057, Some(('5', '5'));
+    Numpad6 => 0x66, 0x004D, 0x0055, 0x0058, Some(('6', '6'));
+    Numpad7 => 0x67, 0x0047, 0x004F, 0x0059, Some(('7', '7'));
+    Numpad8 => 0x68, 0x0048, 0x0050, 0x005B, Some(('8', '8'));
+    Numpad9 => 0x69, 0x0049, 0x0051, 0x005C, Some(('9', '9'));
+    NumpadAdd => 0x6B, 0x004E, 0x0056, 0x0045, Some(('+', '+'));
+    NumpadSubtract => 0x6D, 0x004A, 0x0052, 0x004E, Some(('-', '-'));
+    NumpadMultiply => 0x6A, 0x0037, 0x003F, 0x0043, Some(('*', '*'));
+    NumpadDivide => 0x6F, 0xE035, 0x006A, 0x004B, Some(('/', '/'));
+    NumpadDecimal => 0x6E, 0x0053, 0x005B, 0x0041, Some(('.', '.'));
+    NumpadEqual => 0x92, 0x0059, 0x007D, 0x0051, Some(('=', '='));
+    NumpadEnter => 0x0D, 0xE01C, 0x0068, 0x004C, Some(('\r', '\r'));
+}
+
+impl KeyCode {
+    /// The Windows virtual key code of the key on a US layout.
+    pub fn windows_key_code(self) -> u32 {
+        self.keymap().0
+    }
+
+    /// The PC/AT set 1 scan code of the key, extended keys are prefixed with
+    /// 0xE0, e.g. 0xE048 for `ArrowUp`.
+    pub fn scan_code(self) -> u32 {
+        self.keymap().1
+    }
+
+    /// Find the key of a PC/AT set 1 scan code, see `KeyCode::scan_code`.
+    pub fn from_scan_code(scan_code: u32) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|it| it.scan_code() == scan_code)
+            .copied()
+    }
+
+    /// The default location of the key.
+    pub fn location(self) -> KeyLocation {
+        match self {
+            Self::ShiftLeft | Self::ControlLeft | Self::AltLeft | Self::MetaLeft => {
+                KeyLocation::Left
+            }
+            Self::ShiftRight | Self::ControlRight | Self::AltRight | Self::MetaRight => {
+                KeyLocation::Right
+            }
+            Self::Numpad0
+            | Self::Numpad1
+            | Self::Numpad2
+            | Self::Numpad3
+            | Self::Numpad4
+            | Self::Numpad5
+            | Self::Numpad6
+            | Self::Numpad7
+            | Self::Numpad8
+            | Self::Numpad9
+            | Self::NumpadAdd
+            | Self::NumpadSubtract
+            | Self::NumpadMultiply
+            | Self::NumpadDivide
+            | Self::NumpadDecimal
+            | Self::NumpadEqual
+            | Self::NumpadEnter => KeyLocation::Numpad,
+            _ => KeyLocation::Standard,
+        }
+    }
+}
+
+/// Where a key is on the keyboard, for keys that exist more than once.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyLocation {
+    Standard,
+    Left,
+    Right,
+    Numpad,
+}
+
+/// A key press or release.
+///
+/// A pressed key is sent as a RAWKEYDOWN event followed by a CHAR event for
+/// each UTF-16 code unit of |text|, a released key as a KEYUP event.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyEvent {
+    /// The physical key.
+    pub code: KeyCode,
+    /// The logical key as a Windows virtual key code, the key of |code| on a
+    /// US layout if not set. Set it when the layout differs, e.g. `Z` (0x5A)
+    /// for `KeyCode::KeyY` on a German layout.
+    pub windows_key_code: Option<u32>,
+    /// The text produced by the key, the text of |code| on a US layout with
+    /// shift and caps lock applied if not set. Keys pressed with ctrl, alt or
+    /// the meta key produce no text unless it is set, set it to an empty
+    /// string for keys without text.
+    pub text: Option<String>,
+    pub state: ActionState,
+    /// The key is held down and this is an auto repeated press.
+    pub repeat: bool,
+    pub location: KeyLocation,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode, state: ActionState) -> Self {
+        Self {
+            code,
+            windows_key_code: None,
+            text: None,
+            state,
+            repeat: false,
+            location: code.location(),
+            modifiers: Modifiers::empty(),
+        }
+    }
+}
+
+/// A key event as chromium sees it, see `Observer::on_pre_key_event`.
+///
+/// A key press arrives as a `kKeyRawDown` event followed by a `kKeyChar`
+/// event for each character it produces, match shortcuts on the
+/// `kKeyRawDown` event.
+#[derive(Debug, Clone)]
+pub struct RawKeyEvent {
+    pub ty: KeyEventType,
+    /// The physical key, `None` if it isn't in the keymap.
+    pub code: Option<KeyCode>,
+    /// The Windows virtual key code of the logical key, or the character for
+    /// `kKeyChar` events.
+    pub windows_key_code: u32,
+    /// The lParam of the key message on Windows, the xkb keycode on Linux and
+    /// the key code on macOS.
+    pub native_key_code: i32,
+    /// The character produced by the key, `None` for keys without text.
+    pub character: Option<char>,
+    /// Same as |character| but unmodified by the held modifiers except shift.
+    pub unmodified_character: Option<char>,
+    /// The key is pressed with alt on Windows.
+    pub is_system_key: bool,
+    pub repeat: bool,
+    pub location: KeyLocation,
+    pub modifiers: Modifiers,
+}
+
+pub(crate) mod wrapper {
+    use webview_sys::KeyEventType;
+
+    use super::{KeyCode, KeyEvent, KeyLocation, RawKeyEvent};
+    use crate::Modifiers;
+
+    // Mirrors cef_event_flags_t.
+    const EVENTFLAG_IS_KEY_PAD: u32 = 1 << 9;
+    const EVENTFLAG_IS_LEFT: u32 = 1 << 10;
+    const EVENTFLAG_IS_RIGHT: u32 = 1 << 11;
+    const EVENTFLAG_IS_REPEAT: u32 = 1 << 13;
+
+    impl KeyEvent {
+        /// Translate the key into the events of chromium.
+        pub(crate) fn to_events(&self) -> Vec<webview_sys::KeyEvent> {
+            let (windows_key_code, _, _, _, text) = self.code.keymap();
+            let windows_key_code = self.windows_key_code.unwrap_or(windows_key_code);
+            let mut shift = self.modifiers.contains(Modifiers::SHIFT);
+            let (character, unmodified_character) = match text {
+                Some((lower, upper)) => {
+                    if lower.is_ascii_alphabetic() && self.modifiers.contains(Modifiers::CAPS_LOCK)
+                    {
+                        shift = !shift;
+                    }
+
+                    (if shift { upper } else { lower }, lower)
+                }
+                None => ('\0', '\0'),
+            };
+
+            let text = match &self.text {
+                Some(text) => text.clone(),
+                None if character == '\0'
+                    || self
+                        .modifiers
+                        .intersects(Modifiers::CTRL | Modifiers::ALT | Modifiers::META) =>
+                {
+                    String::new()
+                }
+                None => character.to_string(),
+            };
+
+            let mut modifiers = self.modifiers.bits();
+            modifiers |= match self.location {
+                KeyLocation::Standard => 0,
+                KeyLocation::Left => EVENTFLAG_IS_LEFT,
+                KeyLocation::Right => EVENTFLAG_IS_RIGHT,
+                KeyLocation::Numpad => EVENTFLAG_IS_KEY_PAD,
+            };
+
+            if self.repeat && self.state.is_pressed() {
+                modifiers |= EVENTFLAG_IS_REPEAT;
+            }
+
+            let event = webview_sys::KeyEvent {
+                type_: if self.state.is_pressed() {
+                    KeyEventType::kKeyRawDown
+                } else {
+                    KeyEventType::kKeyUp
+                },
+                modifiers,
+                windows_key_code: windows_key_code as i32,
+                native_key_code: self.native_key_code(),
+                // Always false off Windows, where alt combinations insert text.
+                is_system_key: cfg!(target_os = "windows")
+                    && self.modifiers.contains(Modifiers::ALT),
+                character: text.encode_utf16().next().unwrap_or(0),
+                unmodified_character: unmodified_character as u16,
+            };
+
+            let mut events = vec![event];
+            if self.state.is_pressed() {
+                // A CHAR event carries the character in the windows key code.
+                events.extend(text.encode_utf16().map(|it| webview_sys::KeyEvent {
+                    type_: KeyEventType::kKeyChar,
+                    windows_key_code: it as i32,
+                    character: it,
+                    ..event
+                }));
+            }
+
+            events
+        }
+
+        /// The lParam of the WM_KEYDOWN and WM_KEYUP messages.
+        #[cfg(target_os = "windows")]
+        fn native_key_code(&self) -> i32 {
+            let scan_code = self.code.keymap().1;
+            let mut lparam = 1 | (scan_code & 0xFF) << 16;
+            if scan_code & 0xE000 != 0 {
+                lparam |= 1 << 24;
+            }
+
+            if self.modifiers.contains(Modifiers::ALT) {
+                lparam |= 1 << 29;
+            }
+
+            if self.repeat || !self.state.is_pressed() {
+                lparam |= 1 << 30;
+            }
+
+            if !self.state.is_pressed() {
+                lparam |= 1 << 31;
+            }
+
+            lparam as i32
+        }
+
+        #[cfg(target_os = "macos")]
+        fn native_key_code(&self) -> i32 {
+            self.code.keymap().3 as i32
+        }
+
+        /// The xkb keycode, which is the evdev code plus 8.
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        fn native_key_code(&self) -> i32 {
+            self.code.keymap().2 as i32
+        }
+    }
+
+    impl KeyCode {
+        /// The key of the native key code of a chromium key event.
+        #[cfg(target_os = "windows")]
+        pub(crate) fn from_native_key_code(native_key_code: i32) -> Option<Self> {
+            let mut scan_code = (native_key_code as u32 >> 16) & 0xFF;
+            if native_key_code & 1 << 24 != 0 {
+                scan_code |= 0xE000;
+            }
+
+            Self::from_scan_code(scan_code)
+        }
+
+        #[cfg(target_os = "macos")]
+        pub(crate) fn from_native_key_code(native_key_code: i32) -> Option<Self> {
+            Self::ALL
+                .iter()
+                .find(|it| it.keymap().3 as i32 == native_key_code)
+                .copied()
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        pub(crate) fn from_native_key_code(native_key_code: i32) -> Option<Self> {
+            Self::ALL
+                .iter()
+                .find(|it| it.keymap().2 as i32 == native_key_code)
+                .copied()
+        }
+    }
+
+    impl From<&webview_sys::KeyEvent> for RawKeyEvent {
+        fn from(value: &webview_sys::KeyEvent) -> Self {
+            let character = |it: u16| char::from_u32(it as u32).filter(|it| *it != '\0');
+            let location = if value.modifiers & EVENTFLAG_IS_KEY_PAD != 0 {
+                KeyLocation::Numpad
+            } else if value.modifiers & EVENTFLAG_IS_LEFT != 0 {
+                KeyLocation::Left
+            } else if value.modifiers & EVENTFLAG_IS_RIGHT != 0 {
+                KeyLocation::Right
+            } else {
+                KeyLocation::Standard
+            };
+
+            Self {
+                ty: value.type_,
+                code: KeyCode::from_native_key_code(value.native_key_code),
+                windows_key_code: value.windows_key_code as u32,
+                native_key_code: value.native_key_code,
+                character: character(value.character),
+                unmodified_character: character(value.unmodified_character),
+                is_system_key: value.is_system_key,
+                repeat: value.modifiers & EVENTFLAG_IS_REPEAT != 0,
+                location,
+                modifiers: Modifiers::from_bits_truncate(value.modifiers),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use webview_sys::KeyEventType;
+
+    use super::{KeyCode, KeyEvent};
+    use crate::{ActionState, Modifiers};
+
+    fn press(code: KeyCode, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent {
+            modifiers,
+            ..KeyEvent::new(code, ActionState::Down)
+        }
+    }
+
+    /// The type and character of the events of a key.
+    fn describe(event: &KeyEvent) -> Vec<(KeyEventType, u16)> {
+        event
+            .to_events()
+            .iter()
+            .map(|it| (it.type_, it.character))
+            .collect()
+    }
+
+    #[test]
+    fn press_sends_a_char_per_utf16_unit() {
+        let events = press(KeyCode::KeyA, Modifiers::empty()).to_events();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].type_, KeyEventType::kKeyRawDown);
+        assert_eq!(events[0].windows_key_code, 0x41);
+        assert_eq!(events[0].character, 'a' as u16);
+        assert_eq!(events[1].type_, KeyEventType::kKeyChar);
+        assert_eq!(events[1].windows_key_code, 'a' as i32);
+
+        let event = KeyEvent {
+            text: Some("\u{1F600}".to_string()),
+            ..press(KeyCode::KeyA, Modifiers::empty())
+        };
+
+        assert_eq!(
+            describe(&event),
+            [
+                (KeyEventType::kKeyRawDown, 0xD83D),
+                (KeyEventType::kKeyChar, 0xD83D),
+                (KeyEventType::kKeyChar, 0xDE00),
+            ]
+        );
+
+        let event = KeyEvent {
+            text: Some(String::new()),
+            ..press(KeyCode::KeyA, Modifiers::empty())
+        };
+
+        assert_eq!(describe(&event), [(KeyEventType::kKeyRawDown, 0)]);
+    }
+
+    #[test]
+    fn release_sends_no_char() {
+        let event = KeyEvent::new(KeyCode::KeyA, ActionState::Up);
+
+        assert_eq!(describe(&event), [(KeyEventType::kKeyUp, 'a' as u16)]);
+    }
+
+    #[test]
+    fn caps_lock_inverts_shift_for_letters() {
+        let character = |code, modifiers| press(code, modifiers).to_events()[0].character;
+
+        assert_eq!(character(KeyCode::KeyA, Modifiers::SHIFT), 'A' as u16);
+        assert_eq!(character(KeyCode::KeyA, Modifiers::CAPS_LOCK), 'A' as u16);
+        assert_eq!(
+            character(KeyCode::KeyA, Modifiers::CAPS_LOCK | Modifiers::SHIFT),
+            'a' as u16
+        );
+
+        assert_eq!(character(KeyCode::Digit1, Modifiers::CAPS_LOCK), '1' as u16);
+        assert_eq!(character(KeyCode::Digit1, Modifiers::SHIFT), '!' as u16);
+    }
+
+    #[test]
+    fn shortcuts_have_no_default_text() {
+        for modifiers in [Modifiers::CTRL, Modifiers::ALT, Modifiers::META] {
+            let event = press(KeyCode::KeyA, modifiers);
+            let events = event.to_events();
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].character, 0);
+            assert_eq!(events[0].unmodified_character, 'a' as u16);
+            assert_eq!(
+                events[0].is_system_key,
+                cfg!(target_os = "windows") && modifiers == Modifiers::ALT
+            );
+
+            // Text set by the host is still sent, e.g. AltGr combinations.
+            let event = KeyEvent {
+                text: Some("@".to_string()),
+                ..event
+            };
+
+            assert_eq!(
+                describe(&event),
+                [
+                    (KeyEventType::kKeyRawDown, '@' as u16),
+                    (KeyEventType::kKeyChar, '@' as u16),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn native_key_code_round_trip() {
+        for &code in KeyCode::ALL {
+            // Keys without a macOS key code can not be told apart.
+            if cfg!(target_os = "macos") && code.keymap().3 == 0xFFFF {
+                continue;
+            }
+
+            for state in [ActionState::Down, ActionState::Up] {
+                let native_key_code = KeyEvent::new(code, state).to_events()[0].native_key_code;
+
+                assert_eq!(
+                    KeyCode::from_native_key_code(native_key_code),
+                    Some(code),
+                    "{:?}",
+                    code
+                );
+            }
+        }
+    }
+}
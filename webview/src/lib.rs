@@ -1,30 +1,71 @@
+mod capture;
+mod certificate;
+mod coalesce;
+mod content_blocker;
+mod cursor;
+mod download;
+mod drag;
+mod frame;
+mod ime;
+mod keyboard;
 mod observer;
 mod page;
+mod print;
+mod recorder;
+mod request;
+mod touch;
+
+#[cfg(feature = "winit")]
+pub mod winit;
 
 use std::{
     env::args,
     ffi::{c_char, c_int},
-    sync::{
-        mpsc::channel,
-        Arc, Mutex,
-    },
+    sync::{mpsc::channel, Arc, Mutex},
     thread,
 };
 
-pub use webview_sys::{Modifiers, MouseButtons, PageState, TouchEventType, TouchPointerType};
+use bitflags::bitflags;
+
+pub use webview_sys::{
+    DownloadState, FocusSource, KeyEventType, MouseButtons, PageState, PreKeyEventResult, Rect,
+    ResourceType, TouchEventType, TouchPointerType,
+};
+
+#[cfg(feature = "encoder")]
+pub use self::capture::ImageFormat;
 
 pub use self::{
+    capture::{CaptureOptions, Image},
+    certificate::{
+        Certificate, CertificateError, CertificateErrorCallback, CertificatePins, Fingerprint,
+        SelectClientCertificateCallback,
+    },
+    coalesce::CoalescedInput,
+    content_blocker::ContentBlocker,
+    cursor::{CursorType, CustomCursor},
+    download::{BeforeDownloadCallback, DownloadHandle, DownloadItem},
+    drag::{DragData, DragImage, DragOperations},
+    frame::{copy_dirty_rects, AlphaMode, Frame, PixelFormat},
+    ime::{ImeAction, ImeUnderline, ImeUnderlineStyle},
+    keyboard::{KeyCode, KeyEvent, KeyLocation, RawKeyEvent},
     observer::Observer,
     page::{Page, PageOptions},
+    print::{PaperSize, PdfMargins, PdfOptions},
+    recorder::{ImeInput, InputEvent, InputLog, InputLogEntry},
+    request::{AuthCallback, AuthRequest, Proxy},
+    touch::{TouchEvent, TouchGesture},
 };
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub x: i32,
     pub y: i32,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActionState {
     Down,
     Up,
@@ -36,17 +77,102 @@ impl ActionState {
     }
 }
 
+bitflags! {
+    /// The modifier keys, lock states and mouse buttons of an input event,
+    /// mirrors cef_event_flags_t.
+    ///
+    /// The mouse buttons held down with `MouseAction::Click` are tracked by
+    /// the page and added to later mouse events, so they only need to be set
+    /// for buttons pressed outside of the page.
+    #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Modifiers: u32 {
+        const CAPS_LOCK = 1 << 0;
+        const SHIFT = 1 << 1;
+        const CTRL = 1 << 2;
+        const ALT = 1 << 3;
+        const LEFT_MOUSE_BUTTON = 1 << 4;
+        const MIDDLE_MOUSE_BUTTON = 1 << 5;
+        const RIGHT_MOUSE_BUTTON = 1 << 6;
+        /// The Command key on macOS, the Windows or Super key elsewhere.
+        const META = 1 << 7;
+        const NUM_LOCK = 1 << 8;
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseAction {
-    Click(MouseButtons, ActionState, Option<Position>),
+    /// Press or release |button| at |position|, or at the last position if not
+    /// set.
+    ///
+    /// |click_count| is 1 for a single click, 2 for the second press of a
+    /// double click and so on, use the same count for the release. The back
+    /// and forward buttons navigate the history on release.
+    Click {
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::recorder::remote::MouseButtons")
+        )]
+        button: MouseButtons,
+        state: ActionState,
+        position: Option<Position>,
+        click_count: u32,
+    },
     Move(Position),
-    Wheel(Position),
+    /// Scroll with the mouse at |position|.
+    Wheel {
+        position: Position,
+        delta: WheelDelta,
+    },
+    /// The mouse left the view, e.g. it left the window showing the page.
+    Leave,
 }
 
-#[derive(Debug)]
-pub enum ImeAction<'a> {
-    Composition(&'a str),
-    Pre(&'a str, i32, i32),
+/// The direction focus leaves the page in, see `Observer::on_take_focus`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum FocusDirection {
+    /// Tabbing forward past the last focusable element.
+    Next,
+    /// Tabbing backward past the first focusable element.
+    Previous,
+}
+
+/// The delta of a mouse wheel event.
+///
+/// Positive values move the content right and down, which scrolls the page
+/// left and up, the same as the wheel events of winit.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WheelDelta {
+    /// A delta in view coordinates, e.g. from a touchpad.
+    Pixel(f32, f32),
+    /// A delta in lines, e.g. the notches of a mouse wheel.
+    Line(f32, f32),
+}
+
+impl WheelDelta {
+    /// The pixels of a line, the same as the scrollbar step of chromium.
+    pub const LINE_HEIGHT: f32 = 40.0;
+
+    /// The delta in view coordinates.
+    pub fn to_pixels(self) -> (i32, i32) {
+        let (x, y) = match self {
+            Self::Pixel(x, y) => (x, y),
+            Self::Line(x, y) => (x * Self::LINE_HEIGHT, y * Self::LINE_HEIGHT),
+        };
+
+        (x.round() as i32, y.round() as i32)
+    }
+}
+
+/// CEF only supports fully opaque or fully transparent background colors.
+pub(crate) fn background_color(color: u32) -> u32 {
+    if color >> 24 == 0 {
+        color
+    } else {
+        color | 0xFF000000
+    }
 }
 
 pub(crate) struct Args(Vec<*const c_char>);
@@ -88,17 +214,44 @@ pub fn is_subprocess() -> bool {
     args().find(|v| v.contains("--type")).is_some()
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct WebviewOptions<'a> {
     pub cache_path: Option<&'a str>,
     pub browser_subprocess_path: Option<&'a str>,
     pub scheme_path: Option<&'a str>,
+    /// Proxy of the global request context, the system proxy settings are
+    /// used if not set.
+    pub proxy: Option<Proxy<'a>>,
+    /// The background color in ARGB used before a page is loaded and for
+    /// windowed pages without their own background color, transparent white
+    /// by default.
+    ///
+    /// CEF only supports fully opaque or fully transparent colors, any other
+    /// alpha is treated as opaque.
+    pub background_color: u32,
+}
+
+impl Default for WebviewOptions<'_> {
+    fn default() -> Self {
+        Self {
+            cache_path: None,
+            browser_subprocess_path: None,
+            scheme_path: None,
+            proxy: None,
+            background_color: 0x00FFFFFF,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Error {
     CreateWebviewError,
     CreatePageError,
+    InvalidFingerprint,
+    CaptureError,
+    PrintToPdfError,
+    #[cfg(feature = "encoder")]
+    EncodeImageError(image::ImageError),
 }
 
 impl std::error::Error for Error {}
@@ -178,6 +331,14 @@ impl Webview {
         Page::new(&self, url, settings, observer)
     }
 
+    /// Set the proxy of the global request context.
+    ///
+    /// The change takes effect without restarting and applies to every page
+    /// that does not have its own proxy.
+    pub fn set_proxy(&self, proxy: &Proxy) {
+        self.wrapper.set_proxy(proxy);
+    }
+
     pub fn wait_exit(&self) {
         let _unused = self.condvar.lock().unwrap();
     }
@@ -189,9 +350,12 @@ pub(crate) mod wrapper {
         sync::mpsc::{Receiver, Sender},
     };
 
-    use webview_sys::{create_webview, webview_exit, webview_run, PageState};
+    use webview_sys::{create_webview, webview_exit, webview_run, webview_set_proxy, PageState};
 
-    use crate::{ffi, page::wrapper::Page, Args, Observer, PageOptions, WebviewOptions};
+    use crate::{
+        background_color, ffi, page::wrapper::Page, request::wrapper::ProxyOptions, Args, Observer,
+        PageOptions, Proxy, WebviewOptions,
+    };
 
     /// CefApp
     ///
@@ -231,10 +395,13 @@ pub(crate) mod wrapper {
         }
 
         pub(crate) fn new(options: &WebviewOptions, tx: Sender<()>) -> Option<Self> {
+            let proxy = options.proxy.as_ref().map(ProxyOptions::new);
             let mut options = webview_sys::WebviewOptions {
                 cache_path: ffi::into_opt(options.cache_path),
                 scheme_path: ffi::into_opt(options.scheme_path),
                 browser_subprocess_path: ffi::into_opt(options.browser_subprocess_path),
+                proxy: ProxyOptions::as_ptr(proxy.as_ref()),
+                background_color: background_color(options.background_color),
             };
 
             let raw = unsafe {
@@ -280,6 +447,11 @@ pub(crate) mod wrapper {
             Page::new(&self, url, options, observer)
         }
 
+        pub(crate) fn set_proxy(&self, proxy: &Proxy) {
+            let proxy = ProxyOptions::new(proxy);
+            unsafe { webview_set_proxy(self.0, &proxy.0) }
+        }
+
         pub(crate) fn run(&self) {
             let args = Args::default();
             if unsafe { webview_run(self.0, args.len(), args.as_ptr()) } != 0 {
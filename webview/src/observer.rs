@@ -1,4 +1,12 @@
-use webview_sys::{PageState, Rect};
+use std::ops::Range;
+
+use webview_sys::{FocusSource, PageState, PreKeyEventResult, Rect, ResourceType};
+
+use crate::{
+    AuthCallback, AuthRequest, BeforeDownloadCallback, Certificate, CertificateError,
+    CertificateErrorCallback, CursorType, DownloadHandle, DownloadItem, DragData, DragImage,
+    DragOperations, FocusDirection, Frame, Position, RawKeyEvent, SelectClientCertificateCallback,
+};
 
 #[allow(unused)]
 pub trait Observer: Send + Sync {
@@ -10,21 +18,34 @@ pub trait Observer: Send + Sync {
     fn on_state_change(&self, state: PageState) {}
     /// Called when the IME composition range has changed.
     ///
-    /// selected_range is the range of characters that have been selected.
-    /// |character_bounds| is the bounds of each character in view coordinates.
-    fn on_ime_rect(&self, rect: Rect) {}
-    /// Called when an element should be painted.
-    ///
-    /// Pixel values passed to this method are scaled relative to view
-    /// coordinates based on the value of CefScreenInfo.device_scale_factor
-    /// returned from GetScreenInfo. |type| indicates whether the element is the
-    /// view or the popup widget. |buffer| contains the pixel data for the whole
-    /// image. |dirtyRects| contains the set of rectangles in pixel coordinates
-    /// that need to be repainted. |buffer| will be |width|*|height|*4 bytes in
-    /// size and represents a BGRA image with an upper-left origin. This method
-    /// is only called when CefWindowInfo::shared_texture_enabled is set to
-    /// false.
-    fn on_frame(&self, texture: &[u8], width: u32, height: u32) {}
+    /// |selected_range| is the range of characters that have been selected in
+    /// UTF-16 code units. |character_bounds| is the bounds of each character
+    /// of the composition in view coordinates, e.g. to place the candidate
+    /// window of the input method under the composition.
+    fn on_ime_composition_range_changed(
+        &self,
+        selected_range: Range<u32>,
+        character_bounds: &[Rect],
+    ) {
+    }
+    /// Called when a popup widget (e.g. a `<select>` dropdown) is shown, moved
+    /// or hidden, |rect| is `None` when it is hidden.
+    ///
+    /// In offscreen mode the popup is composited into the frames passed to
+    /// on_frame, so this is only informational. |rect| is in view coordinates.
+    fn on_popup_change(&self, rect: Option<Rect>) {}
+    /// Called when the view of an offscreen page has been painted.
+    ///
+    /// |frame| holds the whole view, with an open popup widget already
+    /// composited in, as premultiplied BGRA rows of |frame.stride| bytes with
+    /// an upper-left origin. Its size is in pixels, the view size times
+    /// |frame.device_scale_factor|, and |frame.dirty_rects| are the pixel
+    /// rectangles that changed since the previous frame.
+    ///
+    /// The |frame| borrows the buffer of chromium, convert or copy it before
+    /// returning. Use `copy_dirty_rects` to update a retained copy of the view
+    /// with only the dirty rects instead of the whole buffer.
+    fn on_frame(&self, frame: &Frame) {}
     /// Called when the page title changes.
     fn on_title_change(&self, title: String) {}
     /// Called when web content in the page has toggled fullscreen mode.
@@ -38,44 +59,207 @@ pub trait Observer: Send + Sync {
     /// The CefWindowDelegate::OnWindowFullscreenTransition method will be
     /// called during the fullscreen transition for notification purposes.
     fn on_fullscreen_change(&self, fullscreen: bool) {}
+    /// Called when the browser's cursor has changed.
+    ///
+    /// Only called for offscreen pages, windowed pages set the native cursor
+    /// themselves. Mirror |cursor| in the host window while the mouse is over
+    /// the page.
+    fn on_cursor_change(&self, cursor: CursorType) {}
+    /// Called when the user starts dragging content in the page.
+    ///
+    /// |allowed| are the operations the page allows and |position| is where
+    /// the drag started in view coordinates. Return true to handle the drag,
+    /// the host then drives it with the `Page::drag_*` methods, starting with
+    /// `Page::drag_enter` without data, and must end it with
+    /// `Page::drag_source_ended`. Return false to cancel the drag.
+    fn on_start_dragging(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+        allowed: DragOperations,
+        position: Position,
+    ) -> bool {
+        false
+    }
+    /// Called when the page has an opinion about the operation of the drag,
+    /// e.g. to show a "no drop" cursor while over a region that refuses it.
+    fn on_update_drag_cursor(&self, operation: DragOperations) {}
+    /// Called when the page is about to give up focus, e.g. when tabbing past
+    /// the last focusable element. The host should move the focus to its next
+    /// or previous control depending on |direction|.
+    fn on_take_focus(&self, direction: FocusDirection) {}
+    /// Called when the page is requesting focus. Return true to cancel
+    /// setting the focus, e.g. to keep it on a host control while a page
+    /// loads.
+    fn on_set_focus(&self, source: FocusSource) -> bool {
+        false
+    }
+    /// Called when the page has received focus.
+    fn on_got_focus(&self) {}
+    /// Called before a key event is sent to the page, for input of the host
+    /// and keys pressed in windowed pages alike.
+    ///
+    /// Return `kPreKeyEventConsume` to handle the key in the host without the
+    /// page seeing it, e.g. for host shortcuts like F5 or to block the
+    /// shortcuts of the page in kiosk mode. Return `kPreKeyEventShortcut` to
+    /// let the page handle the key first, on_key_event is called if it
+    /// doesn't.
+    fn on_pre_key_event(&self, event: &RawKeyEvent) -> PreKeyEventResult {
+        PreKeyEventResult::kPreKeyEventContinue
+    }
+    /// Called after the page didn't handle a key event. Return true if the
+    /// host handled it.
+    fn on_key_event(&self, event: &RawKeyEvent) -> bool {
+        false
+    }
     fn on_message(&self, message: String) {}
+    /// Called when the proxy server requests credentials.
+    ///
+    /// |host| and |port| identify the proxy server and |realm| is the realm
+    /// of the challenge. Answer with `AuthCallback::proceed` now or later from
+    /// any thread, dropping the callback cancels the request.
+    fn on_proxy_auth(&self, host: String, port: u16, realm: String, callback: AuthCallback) {}
+    /// Called when the browser needs credentials from the user.
+    ///
+    /// This covers HTTP Basic/Digest authentication of servers as well as
+    /// proxy servers. Answer with `AuthCallback::proceed` now or later from
+    /// any thread, dropping the callback cancels the request. By default
+    /// proxy requests are forwarded to on_proxy_auth and the others are
+    /// canceled.
+    fn on_auth_credentials(&self, request: AuthRequest, callback: AuthCallback) {
+        if request.is_proxy {
+            self.on_proxy_auth(request.host, request.port, request.realm, callback);
+        }
+    }
+    /// Called before a download begins.
+    ///
+    /// Accept the download and choose its destination with
+    /// `BeforeDownloadCallback::proceed` now or later from any thread,
+    /// dropping the callback rejects the download.
+    fn on_before_download(&self, item: DownloadItem, callback: BeforeDownloadCallback) {}
+    /// Called when a download's status or progress information has been
+    /// updated. This may be called multiple times before and after
+    /// on_before_download.
+    ///
+    /// |handle| can be kept to cancel, pause or resume the download.
+    fn on_download_progress(&self, item: DownloadItem, handle: DownloadHandle) {}
+    /// Called when a download has completed, been canceled or been
+    /// interrupted, |item.state| tells which.
+    ///
+    /// An interrupted download can be resumed with its `DownloadHandle`, so
+    /// this can be called again for the same |item.id| after further
+    /// progress.
+    fn on_download_complete(&self, item: DownloadItem) {}
+    /// Called when a request fails because of an invalid certificate, by
+    /// default the request is denied.
+    ///
+    /// Answer with `CertificateErrorCallback::allow` now or later from any
+    /// thread, dropping the callback denies the request. See
+    /// `CertificatePins` for allowing known self-signed certificates.
+    fn on_certificate_error(&self, error: CertificateError, callback: CertificateErrorCallback) {}
+    /// Called when the server (or the proxy if |is_proxy| is true) at
+    /// |host|:|port| requests a client certificate for authentication.
+    ///
+    /// |certificates| is the list of certificates to choose from, this list
+    /// has already been pruned by Chromium so that it only contains
+    /// certificates from issuers that the server trusts. By default the first
+    /// certificate is selected.
+    fn on_select_client_certificate(
+        &self,
+        host: String,
+        port: u16,
+        is_proxy: bool,
+        certificates: Vec<Certificate>,
+        callback: SelectClientCertificateCallback,
+    ) {
+        callback.select(if certificates.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+    /// Called when the content blocker of the page blocked a request.
+    ///
+    /// |blocked| is the number of requests blocked on the page so far. This
+    /// method is called on the browser process IO thread.
+    fn on_request_blocked(&self, url: String, ty: ResourceType, blocked: u64) {}
 }
 
 pub(crate) mod wrapper {
     use std::{
         ffi::{c_char, c_int, c_void},
+        ptr::null_mut,
         slice::from_raw_parts,
         sync::{
+            atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering},
             mpsc::{channel, Receiver, Sender},
-            Arc,
+            Arc, Mutex,
         },
+        time::Instant,
     };
 
-    use webview_sys::{PageObserver, PageState, Rect};
+    use webview_sys::{
+        Certificate, CursorInfo, CursorType, DownloadItem, DownloadState, DragData, DragImage,
+        FocusSource, ImeRange, KeyEvent, PageObserver, PageState, PreKeyEventResult, Rect,
+        ResourceType,
+    };
 
-    use crate::ffi;
+    use crate::{
+        capture::wrapper::PendingCapture, coalesce::wrapper::InputCoalescer, ffi,
+        ime::wrapper::from_range, page::wrapper::send_input, AlphaMode, AuthCallback, AuthRequest,
+        BeforeDownloadCallback, CertificateError, CertificateErrorCallback, ContentBlocker,
+        DownloadHandle, DragOperations, FocusDirection, Frame, PixelFormat, Position,
+        SelectClientCertificateCallback,
+    };
 
     pub fn create_page_observer() -> PageObserver {
         PageObserver {
             on_state_change: Some(Observer::on_state_change),
-            on_ime_rect: Some(Observer::on_ime_rect),
+            on_ime_composition_range_changed: Some(Observer::on_ime_composition_range_changed),
+            on_popup_change: Some(Observer::on_popup_change),
             on_frame: Some(Observer::on_frame),
             on_title_change: Some(Observer::on_title_change),
             on_fullscreen_change: Some(Observer::on_fullscreen_change),
+            on_cursor_change: Some(Observer::on_cursor_change),
+            on_start_dragging: Some(Observer::on_start_dragging),
+            on_update_drag_cursor: Some(Observer::on_update_drag_cursor),
+            on_take_focus: Some(Observer::on_take_focus),
+            on_set_focus: Some(Observer::on_set_focus),
+            on_got_focus: Some(Observer::on_got_focus),
+            on_pre_key_event: Some(Observer::on_pre_key_event),
+            on_key_event: Some(Observer::on_key_event),
             on_message: Some(Observer::on_message),
+            on_auth_credentials: Some(Observer::on_auth_credentials),
+            on_before_download: Some(Observer::on_before_download),
+            on_download_updated: Some(Observer::on_download_updated),
+            on_certificate_error: Some(Observer::on_certificate_error),
+            on_select_client_certificate: Some(Observer::on_select_client_certificate),
+            on_before_resource_load: Some(Observer::on_before_resource_load),
         }
     }
 
     pub(crate) struct Observer {
         pub inner: Arc<dyn super::Observer>,
         pub sender: Arc<Sender<PageState>>,
+        pub content_blocker: Option<Arc<ContentBlocker>>,
+        pub is_content_blocking: AtomicBool,
+        pub blocked_requests: AtomicU64,
+        pub frames: AtomicU64,
+        pub capture: Mutex<Option<PendingCapture>>,
+        pub coalescer: Mutex<InputCoalescer>,
+        /// The page, null until `create_page` returned and once it exits. Only
+        /// changed with the lock of `coalescer` held.
+        pub page: AtomicPtr<c_void>,
     }
 
     unsafe impl Send for Observer {}
     unsafe impl Sync for Observer {}
 
     impl Observer {
-        pub fn new<T>(observer: T) -> (Self, Receiver<PageState>)
+        pub fn new<T>(
+            observer: T,
+            content_blocker: Option<Arc<ContentBlocker>>,
+        ) -> (Self, Receiver<PageState>)
         where
             T: super::Observer + 'static,
         {
@@ -84,6 +268,13 @@ pub(crate) mod wrapper {
                 Self {
                     inner: Arc::new(observer),
                     sender: Arc::new(tx),
+                    is_content_blocking: AtomicBool::new(content_blocker.is_some()),
+                    blocked_requests: AtomicU64::new(0),
+                    frames: AtomicU64::new(0),
+                    capture: Mutex::new(None),
+                    coalescer: Mutex::new(InputCoalescer::default()),
+                    page: AtomicPtr::new(null_mut()),
+                    content_blocker,
                 },
                 rx,
             )
@@ -108,8 +299,28 @@ pub(crate) mod wrapper {
         ///
         /// selected_range is the range of characters that have been selected.
         /// |character_bounds| is the bounds of each character in view coordinates.
-        extern "C" fn on_ime_rect(rect: Rect, this: *mut c_void) {
-            (unsafe { &*(this as *mut Self) }).inner.on_ime_rect(rect);
+        extern "C" fn on_ime_composition_range_changed(
+            selected_range: ImeRange,
+            character_bounds: *const Rect,
+            character_bounds_size: usize,
+            this: *mut c_void,
+        ) {
+            let character_bounds = if character_bounds.is_null() {
+                &[]
+            } else {
+                unsafe { from_raw_parts(character_bounds, character_bounds_size) }
+            };
+
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_ime_composition_range_changed(from_range(selected_range), character_bounds);
+        }
+
+        /// Called when a popup widget is shown, moved or hidden.
+        extern "C" fn on_popup_change(visible: bool, rect: Rect, this: *mut c_void) {
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_popup_change(if visible { Some(rect) } else { None });
         }
 
         /// Called when an element should be painted.
@@ -127,13 +338,50 @@ pub(crate) mod wrapper {
             texture: *const c_void,
             width: c_int,
             height: c_int,
+            stride: c_int,
+            device_scale_factor: f32,
+            dirty_rects: *const Rect,
+            dirty_rects_size: usize,
+            begin_frame: u64,
             this: *mut c_void,
         ) {
-            (unsafe { &*(this as *mut Self) }).inner.on_frame(
-                unsafe { from_raw_parts(texture as _, width as usize * height as usize * 4) },
-                width as u32,
-                height as u32,
-            );
+            let this = unsafe { &*(this as *mut Self) };
+            let dirty_rects = if dirty_rects.is_null() {
+                &[]
+            } else {
+                unsafe { from_raw_parts(dirty_rects, dirty_rects_size) }
+            };
+
+            let frame = Frame {
+                buffer: unsafe { from_raw_parts(texture as _, stride as usize * height as usize) },
+                width: width as u32,
+                height: height as u32,
+                stride: stride as u32,
+                format: PixelFormat::Bgra,
+                alpha: AlphaMode::Premultiplied,
+                device_scale_factor,
+                timestamp: Instant::now(),
+                sequence: this.frames.fetch_add(1, Ordering::Relaxed),
+                begin_frame: (begin_frame != 0).then_some(begin_frame),
+                dirty_rects,
+            };
+
+            this.inner.on_frame(&frame);
+
+            // Input held back by coalescing waits for the next frame, which is
+            // this one.
+            {
+                let mut coalescer = this.coalescer.lock().unwrap();
+                let page = this.page.load(Ordering::Relaxed);
+                if !page.is_null() {
+                    send_input(page, coalescer.flush());
+                }
+            }
+
+            let mut capture = this.capture.lock().unwrap();
+            if capture.as_ref().map(|it| it.resolve(&frame)) == Some(true) {
+                capture.take();
+            }
         }
 
         /// Called when the page title changes.
@@ -161,10 +409,226 @@ pub(crate) mod wrapper {
                 .on_fullscreen_change(fullscreen);
         }
 
+        /// Called when the browser's cursor has changed.
+        extern "C" fn on_cursor_change(
+            ty: CursorType,
+            custom_cursor: *const CursorInfo,
+            this: *mut c_void,
+        ) {
+            let custom_cursor = unsafe { custom_cursor.as_ref() };
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_cursor_change(crate::CursorType::new(ty, custom_cursor));
+        }
+
+        /// Called when the user starts dragging content in the page.
+        extern "C" fn on_start_dragging(
+            data: *const DragData,
+            image: *const DragImage,
+            allowed_operations: u32,
+            x: c_int,
+            y: c_int,
+            this: *mut c_void,
+        ) -> bool {
+            (unsafe { &*(this as *mut Self) }).inner.on_start_dragging(
+                unsafe { &*data }.into(),
+                unsafe { image.as_ref() }.map(Into::into),
+                DragOperations::from_bits_truncate(allowed_operations),
+                Position { x, y },
+            )
+        }
+
+        /// Called when the page has an opinion about the operation of the
+        /// drag.
+        extern "C" fn on_update_drag_cursor(operation: u32, this: *mut c_void) {
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_update_drag_cursor(DragOperations::from_bits_truncate(operation));
+        }
+
+        /// Called when the browser component is about to lose focus.
+        extern "C" fn on_take_focus(next: bool, this: *mut c_void) {
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_take_focus(if next {
+                    FocusDirection::Next
+                } else {
+                    FocusDirection::Previous
+                });
+        }
+
+        /// Called when the browser component is requesting focus.
+        extern "C" fn on_set_focus(source: FocusSource, this: *mut c_void) -> bool {
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_set_focus(source)
+        }
+
+        /// Called when the browser component has received focus.
+        extern "C" fn on_got_focus(this: *mut c_void) {
+            (unsafe { &*(this as *mut Self) }).inner.on_got_focus();
+        }
+
+        /// Called before a keyboard event is sent to the renderer.
+        extern "C" fn on_pre_key_event(
+            event: *const KeyEvent,
+            this: *mut c_void,
+        ) -> PreKeyEventResult {
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_pre_key_event(&unsafe { &*event }.into())
+        }
+
+        /// Called after the renderer and JavaScript in the page has had a
+        /// chance to handle the event.
+        extern "C" fn on_key_event(event: *const KeyEvent, this: *mut c_void) -> bool {
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_key_event(&unsafe { &*event }.into())
+        }
+
         extern "C" fn on_message(message: *const c_char, this: *mut c_void) {
             if let Some(message) = ffi::from(message) {
                 (unsafe { &*(this as *mut Self) }).inner.on_message(message);
             }
         }
+
+        /// Called when the browser needs credentials from the user.
+        extern "C" fn on_auth_credentials(
+            origin_url: *const c_char,
+            host: *const c_char,
+            port: c_int,
+            realm: *const c_char,
+            scheme: *const c_char,
+            is_proxy: bool,
+            callback: *mut c_void,
+            this: *mut c_void,
+        ) {
+            let callback = AuthCallback::new(callback);
+            let request = AuthRequest {
+                origin_url: ffi::from(origin_url).unwrap_or_default(),
+                host: ffi::from(host).unwrap_or_default(),
+                port: port as u16,
+                realm: ffi::from(realm).unwrap_or_default(),
+                scheme: ffi::from(scheme).unwrap_or_default(),
+                is_proxy,
+            };
+
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_auth_credentials(request, callback);
+        }
+
+        /// Called before a download begins.
+        extern "C" fn on_before_download(
+            item: *const DownloadItem,
+            callback: *mut c_void,
+            this: *mut c_void,
+        ) {
+            let callback = BeforeDownloadCallback::new(callback);
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_before_download(unsafe { &*item }.into(), callback);
+        }
+
+        /// Called when a download's status or progress information has been
+        /// updated.
+        extern "C" fn on_download_updated(
+            item: *const DownloadItem,
+            callback: *mut c_void,
+            this: *mut c_void,
+        ) {
+            let item = unsafe { &*item };
+            let handle = DownloadHandle::new(callback);
+            let this = unsafe { &*(this as *mut Self) };
+
+            if item.state == DownloadState::kDownloadInProgress {
+                this.inner.on_download_progress(item.into(), handle);
+            } else {
+                this.inner.on_download_complete(item.into());
+            }
+        }
+
+        /// Called when a request fails because of an invalid certificate.
+        extern "C" fn on_certificate_error(
+            url: *const c_char,
+            error_code: c_int,
+            certificate: *const Certificate,
+            callback: *mut c_void,
+            this: *mut c_void,
+        ) {
+            let callback = CertificateErrorCallback::new(callback);
+            let error = CertificateError {
+                url: ffi::from(url).unwrap_or_default(),
+                code: error_code,
+                certificate: unsafe { certificate.as_ref() }.map(|it| it.into()),
+            };
+
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_certificate_error(error, callback);
+        }
+
+        /// Called when the server requests a client certificate.
+        extern "C" fn on_select_client_certificate(
+            host: *const c_char,
+            port: c_int,
+            is_proxy: bool,
+            certificates: *const Certificate,
+            certificates_size: usize,
+            callback: *mut c_void,
+            this: *mut c_void,
+        ) {
+            let callback = SelectClientCertificateCallback::new(callback);
+            let certificates = if certificates.is_null() {
+                Vec::new()
+            } else {
+                unsafe { from_raw_parts(certificates, certificates_size) }
+                    .iter()
+                    .map(|it| it.into())
+                    .collect()
+            };
+
+            (unsafe { &*(this as *mut Self) })
+                .inner
+                .on_select_client_certificate(
+                    ffi::from(host).unwrap_or_default(),
+                    port as u16,
+                    is_proxy,
+                    certificates,
+                    callback,
+                );
+        }
+
+        /// Called on the IO thread before a resource request is loaded, returns
+        /// true to block the request.
+        extern "C" fn on_before_resource_load(
+            url: *const c_char,
+            first_party_url: *const c_char,
+            ty: ResourceType,
+            this: *mut c_void,
+        ) -> bool {
+            let this = unsafe { &*(this as *mut Self) };
+            if !this.is_content_blocking.load(Ordering::Relaxed) {
+                return false;
+            }
+
+            let Some(content_blocker) = &this.content_blocker else {
+                return false;
+            };
+
+            let (Some(url), Some(first_party_url)) = (ffi::from(url), ffi::from(first_party_url))
+            else {
+                return false;
+            };
+
+            if !content_blocker.should_block(&url, &first_party_url, ty) {
+                return false;
+            }
+
+            let blocked = this.blocked_requests.fetch_add(1, Ordering::Relaxed) + 1;
+            this.inner.on_request_blocked(url, ty, blocked);
+            true
+        }
     }
 }
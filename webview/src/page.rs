@@ -1,27 +1,60 @@
 use std::{
-    sync::{mpsc::channel, Arc},
+    sync::{
+        atomic::Ordering,
+        mpsc::{channel, Receiver, RecvTimeoutError},
+        Arc,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 use raw_window_handle::RawWindowHandle;
-use webview_sys::{Modifiers, PageState, TouchEventType, TouchPointerType};
+use webview_sys::PageState;
 
-use crate::{ActionState, Error, ImeAction, MouseAction, Observer, Webview};
+use crate::{
+    CaptureOptions, CoalescedInput, ContentBlocker, DragData, DragOperations, Error, Image,
+    ImeAction, InputEvent, InputLog, KeyEvent, Modifiers, MouseAction, Observer, PdfOptions,
+    Position, Proxy, TouchEvent, TouchGesture, Webview,
+};
 
 #[derive(Debug)]
-pub struct PageOptions {
+pub struct PageOptions<'a> {
     pub window_handle: Option<RawWindowHandle>,
     pub frame_rate: u32,
     pub width: u32,
     pub height: u32,
     pub device_scale_factor: f32,
     pub is_offscreen: bool,
+    /// Proxy of the page.
+    ///
+    /// A page with its own proxy gets its own in-memory request context, so
+    /// it does not share cookies, cache and proxy with the other pages. If not
+    /// set, the page uses the global request context of the webview.
+    pub proxy: Option<Proxy<'a>>,
+    /// The content blocker consulted on every resource request of the page,
+    /// it can be shared by many pages.
+    pub content_blocker: Option<Arc<ContentBlocker>>,
+    /// The background color in ARGB painted behind the page content,
+    /// transparent white by default.
+    ///
+    /// A fully transparent color makes offscreen pages transparent wherever
+    /// the page does not paint a background, the frames then carry
+    /// premultiplied alpha (see `Frame::alpha`) and can be composited over the
+    /// host scene. Windowed pages paint an opaque color as well, but use the
+    /// background color of the webview for a transparent one. CEF only
+    /// supports fully opaque or fully transparent colors, any other alpha is
+    /// treated as opaque.
+    pub background_color: u32,
+    /// Paint only on `Page::send_begin_frame` instead of on the internal
+    /// timer of |frame_rate|, for deterministic output in pixel tests and
+    /// video export. Only used when window rendering is disabled.
+    pub external_begin_frame: bool,
 }
 
-unsafe impl Send for PageOptions {}
-unsafe impl Sync for PageOptions {}
+unsafe impl Send for PageOptions<'_> {}
+unsafe impl Sync for PageOptions<'_> {}
 
-impl Default for PageOptions {
+impl Default for PageOptions<'_> {
     fn default() -> Self {
         Self {
             window_handle: None,
@@ -30,6 +63,10 @@ impl Default for PageOptions {
             height: 600,
             device_scale_factor: 1.0,
             is_offscreen: false,
+            proxy: None,
+            content_blocker: None,
+            background_color: 0x00FFFFFF,
+            external_begin_frame: false,
         }
     }
 }
@@ -99,30 +136,55 @@ impl Page {
         Ok(Arc::new(Self(inner)))
     }
 
+    /// Set whether the page is focused.
+    ///
+    /// An offscreen page does not know about the focus of the window showing
+    /// it, call this when the window gains or loses focus so the caret blinks
+    /// and `:focus` styles apply.
+    pub fn set_focus(&self, focus: bool) {
+        self.0.set_focus(focus);
+    }
+
     /// Send a mouse click event to the browser.
     ///
     /// Send a mouse move event to the browser.
     ///
     /// Send a mouse wheel event to the browser.
-    pub fn on_mouse(&self, action: MouseAction) {
-        self.0.on_mouse(action);
+    ///
+    /// Send a mouse leave event to the browser.
+    pub fn on_mouse(&self, action: MouseAction, modifiers: Modifiers) {
+        self.0.on_mouse(action, modifiers);
     }
 
     /// Send a key event to the browser.
-    pub fn on_keyboard(&self, scan_code: u32, state: ActionState, modifiers: Modifiers) {
-        self.0.on_keyboard(scan_code, state, modifiers);
+    pub fn on_keyboard(&self, event: &KeyEvent) {
+        self.0.on_keyboard(event);
     }
 
     /// Send a touch event to the browser for a windowless browser.
-    pub fn on_touch(
-        &self,
-        id: i32,
-        x: i32,
-        y: i32,
-        ty: TouchEventType,
-        pointer_type: TouchPointerType,
-    ) {
-        self.0.on_touch(id, x, y, ty, pointer_type);
+    pub fn on_touch(&self, event: &TouchEvent) {
+        self.0.on_touch(event);
+    }
+
+    /// Perform a two finger gesture for a windowless browser.
+    ///
+    /// The moves are spread over |duration| at 60 moves per second, so this
+    /// blocks until the gesture is done and must not be called from the
+    /// methods of `Observer`.
+    pub fn on_touch_gesture(&self, gesture: &TouchGesture, duration: Duration) {
+        let steps = (duration.as_secs_f32() * 60.0).ceil() as u32;
+        let events = gesture.to_events(steps);
+        let interval = duration / (events.len() as u32 - 1);
+
+        for (index, it) in events.iter().enumerate() {
+            if index > 0 {
+                thread::sleep(interval);
+            }
+
+            for event in it {
+                self.0.on_touch(event);
+            }
+        }
     }
 
     /// Completes the existing composition by optionally inserting the specified
@@ -156,6 +218,50 @@ impl Page {
         self.0.on_ime(action);
     }
 
+    /// Start recording the input sent to the page with `on_mouse`,
+    /// `on_keyboard`, `on_touch` and `on_ime`, a recording in progress is
+    /// discarded.
+    pub fn start_recording(&self) {
+        self.0.start_recording();
+    }
+
+    /// Stop recording the input, `None` if no recording is in progress.
+    pub fn stop_recording(&self) -> Option<InputLog> {
+        self.0.stop_recording()
+    }
+
+    /// Send the input of |log| to the page, with the times of the log divided
+    /// by |speed|.
+    ///
+    /// A |speed| of 1.0 keeps the original timing, 10.0 is ten times faster
+    /// and `f32::INFINITY` sends the input without waiting, as does a zero,
+    /// negative or NaN |speed|. Resize the page to the size of the log first
+    /// so the positions hit the same elements. This blocks until all input is
+    /// sent and must not be called from the methods of `Observer`.
+    pub fn replay(&self, log: &InputLog, speed: f32) {
+        // NaN fails the comparison too.
+        let speed = if speed > 0.0 { speed } else { f32::INFINITY };
+
+        let start = Instant::now();
+        for entry in &log.entries {
+            let time = Duration::try_from_secs_f64(entry.time.as_secs_f64() / f64::from(speed))
+                .unwrap_or(Duration::MAX);
+
+            if let Some(delay) = time.checked_sub(start.elapsed()) {
+                thread::sleep(delay);
+            }
+
+            match &entry.event {
+                InputEvent::Mouse { action, modifiers } => {
+                    self.0.on_mouse(action.clone(), *modifiers)
+                }
+                InputEvent::Keyboard(event) => self.0.on_keyboard(event),
+                InputEvent::Touch(event) => self.0.on_touch(event),
+                InputEvent::Ime(input) => self.0.on_ime(input.as_action()),
+            }
+        }
+    }
+
     /// Notify the browser that the widget has been resized.
     ///
     /// The browser will first call CefRenderHandler::GetViewRect to get the new
@@ -166,6 +272,55 @@ impl Page {
         self.0.resize(width, height);
     }
 
+    /// Set the maximum rate in frames per second (fps) that
+    /// `Observer::on_frame` will be called.
+    ///
+    /// The actual fps may be lower if the browser cannot generate frames at
+    /// the requested rate. The minimum value is 1 and the maximum value is 60.
+    /// This method is only used when window rendering is disabled.
+    pub fn set_frame_rate(&self, frame_rate: u32) {
+        self.0.set_frame_rate(frame_rate.clamp(1, 60));
+    }
+
+    /// Notify the browser that it has been hidden or shown.
+    ///
+    /// Layouting and `Observer::on_frame` notification will stop while the
+    /// page is hidden, which makes it cheap to keep background pages around.
+    /// This method is only used when window rendering is disabled.
+    pub fn set_hidden(&self, hidden: bool) {
+        self.0.set_hidden(hidden);
+    }
+
+    /// Invalidate the view.
+    ///
+    /// The browser will call `Observer::on_frame` asynchronously with a
+    /// complete frame. This method is only used when window rendering is
+    /// disabled.
+    pub fn invalidate(&self) {
+        self.0.invalidate();
+    }
+
+    /// Issue a BeginFrame request to chromium, the page then paints the next
+    /// frame if anything changed.
+    ///
+    /// Returns the id of the request, it is set on the `Frame::begin_frame`
+    /// of the frames painted after it. Only used with
+    /// `PageOptions::external_begin_frame`, returns `None` without it.
+    pub fn send_begin_frame(&self) -> Option<u64> {
+        self.0.flush_input();
+        self.0.send_begin_frame()
+    }
+
+    /// Notify the browser that the screen info has changed, e.g. the window
+    /// moved to a display with a different DPI.
+    ///
+    /// The page keeps its size in view coordinates and the following frames
+    /// are painted with |device_scale_factor|, see `Frame::device_scale_factor`.
+    /// This method is only used when window rendering is disabled.
+    pub fn notify_screen_info_changed(&self, device_scale_factor: f32) {
+        self.0.notify_screen_info_changed(device_scale_factor);
+    }
+
     /// Retrieve the window handle (if any) for this browser.
     ///
     /// If this browser is wrapped in a CefBrowserView this method should be
@@ -185,30 +340,205 @@ impl Page {
     pub fn send_message(&self, message: &str) {
         self.0.send_message(message);
     }
+
+    /// Set the proxy of the request context used by the page.
+    ///
+    /// If the page was created without its own proxy it uses the global
+    /// request context, and the change applies to all pages sharing it.
+    pub fn set_proxy(&self, proxy: &Proxy) {
+        self.0.set_proxy(proxy);
+    }
+
+    /// Enable or disable the content blocker of the page.
+    ///
+    /// Content blocking is enabled by default if the page was created with a
+    /// content blocker, otherwise this has no effect.
+    pub fn set_content_blocking(&self, enabled: bool) {
+        self.0
+            .observer()
+            .is_content_blocking
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// The number of requests blocked by the content blocker of the page.
+    pub fn blocked_requests(&self) -> u64 {
+        self.0.observer().blocked_requests.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable coalescing of mouse moves, mouse wheels and touch
+    /// moves, it is disabled by default.
+    ///
+    /// While enabled these events are held back until the next frame, and
+    /// consecutive events of the same kind are merged into the last move or
+    /// the sum of the wheel deltas. Any other input sends the held back events
+    /// first, so clicks and touch presses happen at the right position. Each
+    /// frame painted by the page and `send_begin_frame` send the held back
+    /// events, a page that paints nothing keeps them until the next input, so
+    /// the host must call `flush_input` once per frame of its own if the
+    /// input can change an idle page, e.g. hover styles. Disabling coalescing
+    /// sends the held back events.
+    pub fn set_input_coalescing(&self, enabled: bool) {
+        self.0.set_input_coalescing(enabled);
+    }
+
+    /// Send the events held back by input coalescing.
+    pub fn flush_input(&self) {
+        self.0.flush_input();
+    }
+
+    /// The number of events merged by input coalescing.
+    pub fn coalesced_input(&self) -> CoalescedInput {
+        self.0.observer().coalescer.lock().unwrap().counts
+    }
+
+    /// Capture the next complete frame of the page.
+    ///
+    /// The view is invalidated and the call blocks until the browser paints
+    /// it, so it must not be called from an `Observer` method and the page
+    /// must not be hidden. With `PageOptions::external_begin_frame` the
+    /// capture sends begin frames itself until the frame is painted. Only one
+    /// capture can be pending at a time, a capture started meanwhile makes
+    /// this one fail. This method is only used when window rendering is
+    /// disabled.
+    pub fn capture(&self, options: &CaptureOptions) -> Result<Image, Error> {
+        if !options.full_page {
+            let receiver = self.0.capture(None, options.clip);
+            self.0.invalidate();
+
+            let image = self.wait_capture(&receiver, options.timeout);
+            self.0.cancel_capture();
+            return image;
+        }
+
+        let (width, height) = self
+            .0
+            .content_size(options.timeout)
+            .ok_or(Error::CaptureError)?;
+
+        // Chromium can not paint views larger than the maximum texture size.
+        let max = (16384.0 / *self.0.device_scale_factor.lock().unwrap()) as u32;
+        let (width, height) = (width.clamp(1, max), height.clamp(1, max));
+        let (view_width, view_height) = *self.0.size.lock().unwrap();
+
+        let receiver = self.0.capture(Some((width, height)), options.clip);
+        self.0.resize(width, height);
+        self.0.invalidate();
+
+        let image = self.wait_capture(&receiver, options.timeout);
+        self.0.cancel_capture();
+        self.0.resize(view_width, view_height);
+        image
+    }
+
+    fn wait_capture(&self, receiver: &Receiver<Image>, timeout: Duration) -> Result<Image, Error> {
+        if !self.0.external_begin_frame {
+            return receiver
+                .recv_timeout(timeout)
+                .map_err(|_| Error::CaptureError);
+        }
+
+        // Nothing paints without begin frames, and the invalidation or resize
+        // may need a few of them to reach the view.
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.send_begin_frame();
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(remaining.min(Duration::from_millis(16))) {
+                Err(RecvTimeoutError::Timeout) if !remaining.is_zero() => (),
+                image => return image.map_err(|_| Error::CaptureError),
+            }
+        }
+    }
+
+    /// Call when the user drags the mouse into the page.
+    ///
+    /// |data| is what the host drags, e.g. files from the file manager, or
+    /// `None` to continue a drag started by the page, see
+    /// `Observer::on_start_dragging`. |allowed| are the operations the drag
+    /// source allows. This method is only used when window rendering is
+    /// disabled.
+    pub fn drag_enter(&self, data: Option<&DragData>, position: Position, allowed: DragOperations) {
+        self.0.drag_enter(data, position, allowed);
+    }
+
+    /// Call each time the mouse is moved across the page during a drag
+    /// operation.
+    pub fn drag_over(&self, position: Position, allowed: DragOperations) {
+        self.0.drag_over(position, allowed);
+    }
+
+    /// Call when the user drags the mouse out of the page.
+    pub fn drag_leave(&self) {
+        self.0.drag_leave();
+    }
+
+    /// Call when the user completes the drag operation by dropping the object
+    /// onto the page.
+    pub fn drag_drop(&self, position: Position) {
+        self.0.drag_drop(position);
+    }
+
+    /// Call when a drag started by the page has ended, either with the
+    /// |operation| performed at |position| or cancelled with an empty
+    /// operation.
+    pub fn drag_source_ended(&self, position: Position, operation: DragOperations) {
+        self.0.drag_source_ended(position, operation);
+    }
+
+    /// Print the page to the PDF file at |path|.
+    ///
+    /// The call blocks until the file is written, so it must not be called
+    /// from an `Observer` method.
+    pub fn print_to_pdf(&self, path: &str, options: &PdfOptions) -> Result<(), Error> {
+        if self.0.print_to_pdf(path, options) {
+            Ok(())
+        } else {
+            Err(Error::PrintToPdfError)
+        }
+    }
 }
 
 pub(crate) mod wrapper {
     use std::{
         ffi::{c_int, c_void},
         num::NonZeroIsize,
-        ptr::null,
-        sync::mpsc::Receiver,
+        ptr::{null, null_mut},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            mpsc::{channel, Receiver, Sender},
+            Mutex,
+        },
+        time::Duration,
     };
 
     use raw_window_handle::{RawWindowHandle, Win32WindowHandle};
     use webview_sys::{
-        create_page, page_exit, page_get_hwnd, page_resize, page_send_ime_composition,
-        page_send_ime_set_composition, page_send_keyboard, page_send_message,
-        page_send_mouse_click, page_send_mouse_click_with_pos, page_send_mouse_move,
-        page_send_mouse_wheel, page_send_touch, page_set_devtools_state, Modifiers, PageState,
-        TouchEventType, TouchPointerType,
+        create_page, page_drag_source_ended, page_drag_target_drag_enter,
+        page_drag_target_drag_leave, page_drag_target_drag_over, page_drag_target_drop, page_exit,
+        page_get_content_size, page_get_hwnd, page_invalidate, page_notify_screen_info_changed,
+        page_print_to_pdf, page_resize, page_send_begin_frame, page_send_ime_cancel_composition,
+        page_send_ime_commit_text, page_send_ime_finish_composing_text,
+        page_send_ime_set_composition, page_send_key_event, page_send_message,
+        page_send_mouse_click, page_send_mouse_click_with_pos, page_send_mouse_leave,
+        page_send_mouse_move, page_send_mouse_wheel, page_send_touch, page_set_devtools_state,
+        page_set_focus, page_set_frame_rate, page_set_hidden, page_set_proxy, PageState, Rect,
     };
 
     use crate::{
+        background_color,
+        capture::wrapper::PendingCapture,
+        coalesce::wrapper::PendingInput,
+        drag::wrapper::DragDataWrapper,
         ffi,
+        ime::wrapper::to_range,
         observer::wrapper::{create_page_observer, Observer as ObserverWrapper},
+        print::wrapper::PdfOptions as PdfOptionsWrapper,
+        recorder::wrapper::Recording,
+        request::wrapper::ProxyOptions,
         wrapper::Webview,
-        ActionState, ImeAction, MouseAction, Observer,
+        DragData, DragOperations, Image, ImeAction, InputEvent, InputLog, KeyEvent, Modifiers,
+        MouseAction, Observer, PdfOptions, Position, Proxy, TouchEvent,
     };
 
     use super::PageOptions;
@@ -233,6 +563,14 @@ pub(crate) mod wrapper {
     pub(crate) struct Page {
         pub observer: *mut ObserverWrapper,
         pub raw: *mut c_void,
+        /// The view size and device scale factor last set, they are restored
+        /// after a full page capture.
+        pub size: Mutex<(u32, u32)>,
+        pub device_scale_factor: Mutex<f32>,
+        pub recording: Mutex<Option<Recording>>,
+        /// Whether the page only paints on begin frames sent by the host.
+        pub external_begin_frame: bool,
+        pub begin_frames: AtomicU64,
     }
 
     unsafe impl Send for Page {}
@@ -248,6 +586,8 @@ pub(crate) mod wrapper {
         where
             T: Observer + 'static,
         {
+            let proxy = options.proxy.as_ref().map(ProxyOptions::new);
+            let content_blocker = options.content_blocker.clone();
             let options = webview_sys::PageOptions {
                 frame_rate: options.frame_rate,
                 width: options.width,
@@ -262,9 +602,14 @@ pub(crate) mod wrapper {
                 } else {
                     null()
                 },
+                proxy: ProxyOptions::as_ptr(proxy.as_ref()),
+                background_color: background_color(options.background_color),
+                external_begin_frame: options.external_begin_frame,
             };
 
-            let (observer, rx) = ObserverWrapper::new(observer);
+            let size = (options.width, options.height);
+            let device_scale_factor = options.device_scale_factor;
+            let (observer, rx) = ObserverWrapper::new(observer, content_blocker);
             let observer = Box::into_raw(Box::new(observer));
 
             let url = ffi::into(url);
@@ -282,7 +627,25 @@ pub(crate) mod wrapper {
                 ffi::free(url);
             }
 
-            (Self { observer, raw }, rx)
+            // Lets the observer send the input held back until a frame.
+            {
+                let observer = unsafe { &*observer };
+                let _coalescer = observer.coalescer.lock().unwrap();
+                observer.page.store(raw, Ordering::Relaxed);
+            }
+
+            (
+                Self {
+                    observer,
+                    raw,
+                    size: Mutex::new(size),
+                    device_scale_factor: Mutex::new(device_scale_factor),
+                    recording: Mutex::new(None),
+                    external_begin_frame: options.is_offscreen && options.external_begin_frame,
+                    begin_frames: AtomicU64::new(0),
+                },
+                rx,
+            )
         }
 
         pub(crate) fn send_message(&self, message: &str) {
@@ -295,50 +658,162 @@ pub(crate) mod wrapper {
             ffi::free(message);
         }
 
+        pub(crate) fn observer(&self) -> &ObserverWrapper {
+            unsafe { &*self.observer }
+        }
+
+        extern "C" fn content_size_callback(
+            success: bool,
+            width: c_int,
+            height: c_int,
+            ctx: *mut c_void,
+        ) {
+            let sender = unsafe { Box::from_raw(ctx as *mut Sender<Option<(u32, u32)>>) };
+
+            // The receiver may be gone after a timeout.
+            let _ = sender.send(if success {
+                Some((width.max(0) as u32, height.max(0) as u32))
+            } else {
+                None
+            });
+        }
+
+        /// Get the size of the page content in view coordinates.
+        pub(crate) fn content_size(&self, timeout: Duration) -> Option<(u32, u32)> {
+            let (tx, rx) = channel();
+            unsafe {
+                page_get_content_size(
+                    self.raw,
+                    timeout.as_millis().min(u32::MAX as u128) as u32,
+                    Some(Self::content_size_callback),
+                    Box::into_raw(Box::new(tx)) as *mut _,
+                )
+            }
+
+            rx.recv_timeout(timeout).ok().flatten()
+        }
+
+        /// Register a capture resolved by the next frame of the expected
+        /// |size|, replacing a capture still pending.
+        pub(crate) fn capture(
+            &self,
+            size: Option<(u32, u32)>,
+            clip: Option<Rect>,
+        ) -> Receiver<Image> {
+            let (sender, receiver) = channel();
+            *self.observer().capture.lock().unwrap() = Some(PendingCapture { size, clip, sender });
+            receiver
+        }
+
+        pub(crate) fn cancel_capture(&self) {
+            self.observer().capture.lock().unwrap().take();
+        }
+
+        pub(crate) fn drag_enter(
+            &self,
+            data: Option<&DragData>,
+            position: Position,
+            allowed: DragOperations,
+        ) {
+            let data = data.map(DragDataWrapper::new);
+            unsafe {
+                page_drag_target_drag_enter(
+                    self.raw,
+                    data.as_ref()
+                        .map(|it| &it.inner as *const _)
+                        .unwrap_or_else(null),
+                    position.x,
+                    position.y,
+                    allowed.bits(),
+                )
+            }
+        }
+
+        pub(crate) fn drag_over(&self, position: Position, allowed: DragOperations) {
+            unsafe { page_drag_target_drag_over(self.raw, position.x, position.y, allowed.bits()) }
+        }
+
+        pub(crate) fn drag_leave(&self) {
+            unsafe { page_drag_target_drag_leave(self.raw) }
+        }
+
+        pub(crate) fn drag_drop(&self, position: Position) {
+            unsafe { page_drag_target_drop(self.raw, position.x, position.y) }
+        }
+
+        pub(crate) fn drag_source_ended(&self, position: Position, operation: DragOperations) {
+            unsafe { page_drag_source_ended(self.raw, position.x, position.y, operation.bits()) }
+        }
+
+        extern "C" fn print_to_pdf_callback(success: bool, ctx: *mut c_void) {
+            let sender = unsafe { Box::from_raw(ctx as *mut Sender<bool>) };
+            let _ = sender.send(success);
+        }
+
+        pub(crate) fn print_to_pdf(&self, path: &str, options: &PdfOptions) -> bool {
+            let options = PdfOptionsWrapper::new(options);
+            let path = ffi::into(path);
+
+            let (tx, rx) = channel();
+            unsafe {
+                page_print_to_pdf(
+                    self.raw,
+                    path,
+                    &options.0,
+                    Some(Self::print_to_pdf_callback),
+                    Box::into_raw(Box::new(tx)) as *mut _,
+                )
+            }
+
+            ffi::free(path);
+            rx.recv().unwrap_or(false)
+        }
+
+        pub(crate) fn set_proxy(&self, proxy: &Proxy) {
+            let proxy = ProxyOptions::new(proxy);
+            unsafe { page_set_proxy(self.raw, &proxy.0) }
+        }
+
+        /// Set whether the browser is focused.
+        pub fn set_focus(&self, focus: bool) {
+            unsafe { page_set_focus(self.raw, focus) }
+        }
+
         /// Send a mouse click event to the browser.
         ///
         /// Send a mouse move event to the browser.
         ///
         /// Send a mouse wheel event to the browser.
-        pub fn on_mouse(&self, action: MouseAction) {
-            match action {
-                MouseAction::Move(pos) => unsafe { page_send_mouse_move(self.raw, pos.x, pos.y) },
-                MouseAction::Wheel(pos) => unsafe { page_send_mouse_wheel(self.raw, pos.x, pos.y) },
-                MouseAction::Click(button, state, pos) => {
-                    if let Some(pos) = pos {
-                        unsafe {
-                            page_send_mouse_click_with_pos(
-                                self.raw,
-                                button,
-                                state.is_pressed(),
-                                pos.x,
-                                pos.y,
-                            )
-                        }
-                    } else {
-                        unsafe { page_send_mouse_click(self.raw, button, state.is_pressed()) }
-                    }
-                }
-            }
+        ///
+        /// Send a mouse leave event to the browser.
+        pub fn on_mouse(&self, action: MouseAction, modifiers: Modifiers) {
+            self.record(|| InputEvent::Mouse {
+                action: action.clone(),
+                modifiers,
+            });
+
+            // Sent under the lock, so the frames can not send held back events
+            // in between.
+            let mut coalescer = self.observer().coalescer.lock().unwrap();
+            send_input(self.raw, coalescer.mouse(action, modifiers));
         }
 
         /// Send a key event to the browser.
-        pub fn on_keyboard(&self, scan_code: u32, state: ActionState, modifiers: Modifiers) {
-            unsafe {
-                page_send_keyboard(self.raw, scan_code as c_int, state.is_pressed(), modifiers)
+        pub fn on_keyboard(&self, event: &KeyEvent) {
+            self.record(|| InputEvent::Keyboard(event.clone()));
+            self.flush_input();
+
+            for it in event.to_events() {
+                unsafe { page_send_key_event(self.raw, &it) }
             }
         }
 
         /// Send a touch event to the browser for a windowless browser.
-        pub fn on_touch(
-            &self,
-            id: i32,
-            x: i32,
-            y: i32,
-            ty: TouchEventType,
-            pointer_type: TouchPointerType,
-        ) {
-            unsafe { page_send_touch(self.raw, id, x, y, ty, pointer_type) }
+        pub fn on_touch(&self, event: &TouchEvent) {
+            self.record(|| InputEvent::Touch(*event));
+
+            let mut coalescer = self.observer().coalescer.lock().unwrap();
+            send_input(self.raw, coalescer.touch(*event));
         }
 
         /// Completes the existing composition by optionally inserting the specified
@@ -369,18 +844,84 @@ pub(crate) mod wrapper {
         ///
         /// This method is only used when window rendering is disabled.
         pub fn on_ime(&self, action: ImeAction) {
-            let input = match action {
-                ImeAction::Composition(it) | ImeAction::Pre(it, _, _) => ffi::into(it),
-            };
+            self.record(|| InputEvent::Ime((&action).into()));
+            self.flush_input();
 
             match action {
-                ImeAction::Composition(_) => unsafe { page_send_ime_composition(self.raw, input) },
-                ImeAction::Pre(_, x, y) => unsafe {
-                    page_send_ime_set_composition(self.raw, input, x, y)
+                ImeAction::SetComposition {
+                    text,
+                    underlines,
+                    replacement_range,
+                    selection_range,
+                } => {
+                    let text = ffi::into(text);
+                    let underlines = underlines.iter().map(|it| it.to_raw()).collect::<Vec<_>>();
+                    let replacement_range = replacement_range.as_ref().map(to_range);
+                    let selection_range = selection_range.as_ref().map(to_range);
+
+                    unsafe {
+                        page_send_ime_set_composition(
+                            self.raw,
+                            text,
+                            underlines.as_ptr(),
+                            underlines.len(),
+                            replacement_range.as_ref().map_or(null(), |it| it),
+                            selection_range.as_ref().map_or(null(), |it| it),
+                        )
+                    }
+
+                    ffi::free(text);
+                }
+                ImeAction::Commit {
+                    text,
+                    replacement_range,
+                    relative_cursor_position,
+                } => {
+                    let text = ffi::into(text);
+                    let replacement_range = replacement_range.as_ref().map(to_range);
+
+                    unsafe {
+                        page_send_ime_commit_text(
+                            self.raw,
+                            text,
+                            replacement_range.as_ref().map_or(null(), |it| it),
+                            relative_cursor_position,
+                        )
+                    }
+
+                    ffi::free(text);
+                }
+                ImeAction::Finish { keep_selection } => unsafe {
+                    page_send_ime_finish_composing_text(self.raw, keep_selection)
                 },
+                ImeAction::Cancel => unsafe { page_send_ime_cancel_composition(self.raw) },
             }
+        }
+
+        pub fn start_recording(&self) {
+            let (width, height) = *self.size.lock().unwrap();
+            *self.recording.lock().unwrap() = Some(Recording::new(width, height));
+        }
 
-            ffi::free(input);
+        pub fn stop_recording(&self) -> Option<InputLog> {
+            self.recording.lock().unwrap().take().map(Recording::finish)
+        }
+
+        pub fn set_input_coalescing(&self, enabled: bool) {
+            let mut coalescer = self.observer().coalescer.lock().unwrap();
+            coalescer.enabled = enabled;
+            send_input(self.raw, coalescer.flush());
+        }
+
+        pub fn flush_input(&self) {
+            let mut coalescer = self.observer().coalescer.lock().unwrap();
+            send_input(self.raw, coalescer.flush());
+        }
+
+        fn record(&self, event: impl FnOnce() -> InputEvent) {
+            if let Some(recording) = self.recording.lock().unwrap().as_mut() {
+                recording.push(event());
+            }
         }
 
         /// Notify the browser that the widget has been resized.
@@ -390,9 +931,44 @@ pub(crate) mod wrapper {
         /// updated regions. This method is only used when window rendering is
         /// disabled.
         pub fn resize(&self, width: u32, height: u32) {
+            *self.size.lock().unwrap() = (width, height);
             unsafe { page_resize(self.raw, width as c_int, height as c_int) }
         }
 
+        /// Set the maximum rate in frames per second (fps) that on_frame will
+        /// be called.
+        pub fn set_frame_rate(&self, frame_rate: u32) {
+            unsafe { page_set_frame_rate(self.raw, frame_rate as c_int) }
+        }
+
+        /// Notify the browser that it has been hidden or shown.
+        pub fn set_hidden(&self, hidden: bool) {
+            unsafe { page_set_hidden(self.raw, hidden) }
+        }
+
+        /// Invalidate the view.
+        pub fn invalidate(&self) {
+            unsafe { page_invalidate(self.raw) }
+        }
+
+        /// Issue a BeginFrame request to chromium, returns its id or `None`
+        /// without external begin frames.
+        pub fn send_begin_frame(&self) -> Option<u64> {
+            if !self.external_begin_frame {
+                return None;
+            }
+
+            let id = self.begin_frames.fetch_add(1, Ordering::Relaxed) + 1;
+            unsafe { page_send_begin_frame(self.raw, id) }
+            Some(id)
+        }
+
+        /// Change the device scale factor of the screen.
+        pub fn notify_screen_info_changed(&self, device_scale_factor: f32) {
+            *self.device_scale_factor.lock().unwrap() = device_scale_factor;
+            unsafe { page_notify_screen_info_changed(self.raw, device_scale_factor) }
+        }
+
         /// Retrieve the window handle (if any) for this browser.
         ///
         /// If this browser is wrapped in a CefBrowserView this method should be
@@ -414,6 +990,12 @@ pub(crate) mod wrapper {
 
     impl Drop for Page {
         fn drop(&mut self) {
+            // A frame painted while the page exits must not send input to it.
+            {
+                let _coalescer = self.observer().coalescer.lock().unwrap();
+                self.observer().page.store(null_mut(), Ordering::Relaxed);
+            }
+
             unsafe {
                 page_exit(self.raw);
             }
@@ -421,4 +1003,64 @@ pub(crate) mod wrapper {
             drop(unsafe { Box::from_raw(self.observer) });
         }
     }
+
+    /// Send the input released by the coalescer of the page, with the lock of
+    /// the coalescer held to keep the order of the input.
+    pub(crate) fn send_input(raw: *mut c_void, events: Vec<PendingInput>) {
+        for it in events {
+            match it {
+                PendingInput::Mouse(action, modifiers) => send_mouse(raw, action, modifiers),
+                PendingInput::Touch(event) => send_touch(raw, &event),
+            }
+        }
+    }
+
+    fn send_mouse(raw: *mut c_void, action: MouseAction, modifiers: Modifiers) {
+        let modifiers = modifiers.bits();
+        match action {
+            MouseAction::Move(pos) => unsafe { page_send_mouse_move(raw, pos.x, pos.y, modifiers) },
+            MouseAction::Wheel { position, delta } => {
+                let (delta_x, delta_y) = delta.to_pixels();
+                unsafe {
+                    page_send_mouse_wheel(raw, position.x, position.y, delta_x, delta_y, modifiers)
+                }
+            }
+            MouseAction::Click {
+                button,
+                state,
+                position,
+                click_count,
+            } => {
+                let click_count = click_count.clamp(1, i32::MAX as u32) as c_int;
+                if let Some(pos) = position {
+                    unsafe {
+                        page_send_mouse_click_with_pos(
+                            raw,
+                            button,
+                            state.is_pressed(),
+                            pos.x,
+                            pos.y,
+                            click_count,
+                            modifiers,
+                        )
+                    }
+                } else {
+                    unsafe {
+                        page_send_mouse_click(
+                            raw,
+                            button,
+                            state.is_pressed(),
+                            click_count,
+                            modifiers,
+                        )
+                    }
+                }
+            }
+            MouseAction::Leave => unsafe { page_send_mouse_leave(raw, modifiers) },
+        }
+    }
+
+    fn send_touch(raw: *mut c_void, event: &TouchEvent) {
+        unsafe { page_send_touch(raw, &event.to_raw()) }
+    }
 }
@@ -0,0 +1,150 @@
+/// The paper size of a PDF.
+#[derive(Debug, Clone, Copy)]
+pub enum PaperSize {
+    /// US Letter, 8.5 x 11 inches.
+    Letter,
+    /// US Legal, 8.5 x 14 inches.
+    Legal,
+    /// A3, 297 x 420 mm.
+    A3,
+    /// A4, 210 x 297 mm.
+    A4,
+    /// A5, 148 x 210 mm.
+    A5,
+    /// A custom size with the width and height in inches.
+    Custom(f64, f64),
+}
+
+impl PaperSize {
+    /// The width and height in inches.
+    pub fn size(self) -> (f64, f64) {
+        match self {
+            Self::Letter => (8.5, 11.0),
+            Self::Legal => (8.5, 14.0),
+            Self::A3 => (297.0 / 25.4, 420.0 / 25.4),
+            Self::A4 => (210.0 / 25.4, 297.0 / 25.4),
+            Self::A5 => (148.0 / 25.4, 210.0 / 25.4),
+            Self::Custom(width, height) => (width, height),
+        }
+    }
+}
+
+/// The margins of a PDF.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PdfMargins {
+    /// The default margins of 1cm (~0.4 inches).
+    #[default]
+    Default,
+    /// No margins.
+    None,
+    /// Custom margins in inches.
+    Custom {
+        top: f64,
+        right: f64,
+        bottom: f64,
+        left: f64,
+    },
+}
+
+/// Options of `Page::print_to_pdf`.
+#[derive(Debug, Clone, Copy)]
+pub struct PdfOptions<'a> {
+    /// Set to true for landscape mode or false for portrait mode.
+    pub landscape: bool,
+    /// Set to true to print background graphics.
+    pub print_background: bool,
+    /// The scale of the page rendering, between 0.1 and 2.
+    pub scale: f64,
+    pub paper_size: PaperSize,
+    /// Set to true to prefer page size as defined by css. Defaults to false,
+    /// in which case the content will be scaled to fit the paper size.
+    pub prefer_css_page_size: bool,
+    pub margins: PdfMargins,
+    /// Paper ranges to print, one based, e.g., "1-5, 8, 11-13". Pages are
+    /// printed in the document order, not in the order specified, and no more
+    /// than once. All pages are printed if not set.
+    pub page_ranges: Option<&'a str>,
+    /// HTML template for the print header.
+    ///
+    /// The header and footer are only displayed if either template is set, a
+    /// missing one then uses the default template of chromium.
+    ///
+    /// Should be valid HTML markup with the following classes used to inject
+    /// printing values into them: `date` for the formatted print date, `title`
+    /// for the document title, `url` for the document location, `pageNumber`
+    /// for the current page number and `totalPages` for the total pages in the
+    /// document. For example, `<span class=title></span>` would generate a
+    /// span containing the title.
+    pub header_template: Option<&'a str>,
+    /// HTML template for the print footer, see |header_template|.
+    pub footer_template: Option<&'a str>,
+}
+
+impl Default for PdfOptions<'_> {
+    fn default() -> Self {
+        Self {
+            landscape: false,
+            print_background: false,
+            scale: 1.0,
+            paper_size: PaperSize::Letter,
+            prefer_css_page_size: false,
+            margins: PdfMargins::Default,
+            page_ranges: None,
+            header_template: None,
+            footer_template: None,
+        }
+    }
+}
+
+pub(crate) mod wrapper {
+    use webview_sys::PdfMarginType;
+
+    use super::{PdfMargins, PdfOptions as Options};
+    use crate::ffi;
+
+    pub(crate) struct PdfOptions(pub webview_sys::PdfOptions);
+
+    impl PdfOptions {
+        pub(crate) fn new(options: &Options) -> Self {
+            let (paper_width, paper_height) = options.paper_size.size();
+            let (margin_type, [margin_top, margin_right, margin_bottom, margin_left]) =
+                match options.margins {
+                    PdfMargins::Default => (PdfMarginType::kPdfMarginDefault, [0.0; 4]),
+                    PdfMargins::None => (PdfMarginType::kPdfMarginNone, [0.0; 4]),
+                    PdfMargins::Custom {
+                        top,
+                        right,
+                        bottom,
+                        left,
+                    } => (PdfMarginType::kPdfMarginCustom, [top, right, bottom, left]),
+                };
+
+            Self(webview_sys::PdfOptions {
+                landscape: options.landscape,
+                print_background: options.print_background,
+                scale: options.scale.clamp(0.1, 2.0),
+                paper_width,
+                paper_height,
+                prefer_css_page_size: options.prefer_css_page_size,
+                margin_type,
+                margin_top,
+                margin_right,
+                margin_bottom,
+                margin_left,
+                page_ranges: ffi::into_opt(options.page_ranges),
+                display_header_footer: options.header_template.is_some()
+                    || options.footer_template.is_some(),
+                header_template: ffi::into_opt(options.header_template),
+                footer_template: ffi::into_opt(options.footer_template),
+            })
+        }
+    }
+
+    impl Drop for PdfOptions {
+        fn drop(&mut self) {
+            ffi::free(self.0.page_ranges);
+            ffi::free(self.0.header_template);
+            ffi::free(self.0.footer_template);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::{ops::Range, time::Duration};
+
+use crate::{ImeAction, ImeUnderline, KeyEvent, Modifiers, MouseAction, TouchEvent};
+
+/// An owned `ImeAction`, as kept in an `InputLog`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub enum ImeInput {
+    SetComposition {
+        text: String,
+        underlines: Vec<ImeUnderline>,
+        replacement_range: Option<Range<u32>>,
+        selection_range: Option<Range<u32>>,
+    },
+    Commit {
+        text: String,
+        replacement_range: Option<Range<u32>>,
+        relative_cursor_position: i32,
+    },
+    Finish {
+        keep_selection: bool,
+    },
+    Cancel,
+}
+
+impl ImeInput {
+    pub fn as_action(&self) -> ImeAction<'_> {
+        match self {
+            Self::SetComposition {
+                text,
+                underlines,
+                replacement_range,
+                selection_range,
+            } => ImeAction::SetComposition {
+                text,
+                underlines,
+                replacement_range: replacement_range.clone(),
+                selection_range: selection_range.clone(),
+            },
+            Self::Commit {
+                text,
+                replacement_range,
+                relative_cursor_position,
+            } => ImeAction::Commit {
+                text,
+                replacement_range: replacement_range.clone(),
+                relative_cursor_position: *relative_cursor_position,
+            },
+            Self::Finish { keep_selection } => ImeAction::Finish {
+                keep_selection: *keep_selection,
+            },
+            Self::Cancel => ImeAction::Cancel,
+        }
+    }
+}
+
+impl From<&ImeAction<'_>> for ImeInput {
+    fn from(value: &ImeAction<'_>) -> Self {
+        match value {
+            ImeAction::SetComposition {
+                text,
+                underlines,
+                replacement_range,
+                selection_range,
+            } => Self::SetComposition {
+                text: text.to_string(),
+                underlines: underlines.to_vec(),
+                replacement_range: replacement_range.clone(),
+                selection_range: selection_range.clone(),
+            },
+            ImeAction::Commit {
+                text,
+                replacement_range,
+                relative_cursor_position,
+            } => Self::Commit {
+                text: text.to_string(),
+                replacement_range: replacement_range.clone(),
+                relative_cursor_position: *relative_cursor_position,
+            },
+            ImeAction::Finish { keep_selection } => Self::Finish {
+                keep_selection: *keep_selection,
+            },
+            ImeAction::Cancel => Self::Cancel,
+        }
+    }
+}
+
+/// An input sent to a page with `Page::on_mouse`, `Page::on_keyboard`,
+/// `Page::on_touch` or `Page::on_ime`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub enum InputEvent {
+    Mouse {
+        action: MouseAction,
+        modifiers: Modifiers,
+    },
+    Keyboard(KeyEvent),
+    Touch(TouchEvent),
+    Ime(ImeInput),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct InputLogEntry {
+    /// The time since the recording started.
+    pub time: Duration,
+    pub event: InputEvent,
+}
+
+/// The input sent to a page between `Page::start_recording` and
+/// `Page::stop_recording`, replay it with `Page::replay`.
+///
+/// Serialize it with the `serde` feature, e.g. to attach it to a bug report.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct InputLog {
+    /// The view size when the recording started, positions in the log are
+    /// relative to it.
+    pub width: u32,
+    pub height: u32,
+    pub entries: Vec<InputLogEntry>,
+}
+
+impl InputLog {
+    /// The time of the last input.
+    pub fn duration(&self) -> Duration {
+        self.entries.last().map(|it| it.time).unwrap_or_default()
+    }
+}
+
+/// Serde definitions of the enums of webview-sys.
+#[cfg(feature = "serde")]
+#[allow(non_camel_case_types, clippy::enum_variant_names)]
+pub(crate) mod remote {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "webview_sys::MouseButtons")]
+    pub(crate) enum MouseButtons {
+        kLeft,
+        kRight,
+        kMiddle,
+        kBack,
+        kForward,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "webview_sys::TouchEventType")]
+    pub(crate) enum TouchEventType {
+        kTouchReleased,
+        kTouchPressed,
+        kTouchMoved,
+        kTouchCancelled,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "webview_sys::TouchPointerType")]
+    pub(crate) enum TouchPointerType {
+        kTouch,
+        kMouse,
+        kPen,
+        kEraser,
+        kUnknown,
+    }
+}
+
+pub(crate) mod wrapper {
+    use std::time::Instant;
+
+    use super::{InputEvent, InputLog, InputLogEntry};
+
+    /// A recording started with `Page::start_recording`.
+    pub(crate) struct Recording {
+        start: Instant,
+        log: InputLog,
+    }
+
+    impl Recording {
+        pub(crate) fn new(width: u32, height: u32) -> Self {
+            Self {
+                start: Instant::now(),
+                log: InputLog {
+                    width,
+                    height,
+                    entries: Vec::new(),
+                },
+            }
+        }
+
+        pub(crate) fn push(&mut self, event: InputEvent) {
+            self.log.entries.push(InputLogEntry {
+                time: self.start.elapsed(),
+                event,
+            });
+        }
+
+        pub(crate) fn finish(self) -> InputLog {
+            self.log
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::ffi::c_void;
+
+use webview_sys::{auth_callback_cancel, auth_callback_continue};
+
+use crate::ffi;
+
+/// Proxy settings of a request context.
+///
+/// The settings are applied through the chromium "proxy" preference, so they
+/// can be changed at runtime without restarting the webview.
+#[derive(Debug, Clone, Copy)]
+pub enum Proxy<'a> {
+    /// Never use a proxy.
+    Direct,
+    /// Use the fixed proxy servers.
+    ///
+    /// |server| is a proxy rule such as `"127.0.0.1:8080"`,
+    /// `"socks5://127.0.0.1:1080"` or `"http=proxy:80;https=proxy:443"`.
+    /// Hosts in |bypass_list| (e.g. `"localhost;*.example.com"`) are connected
+    /// directly.
+    FixedServers {
+        server: &'a str,
+        bypass_list: Option<&'a str>,
+    },
+    /// Use the proxy auto-config (PAC) script at the url.
+    PacScript(&'a str),
+    /// Use the proxy settings of the operating system.
+    System,
+}
+
+/// A request for credentials by a server using HTTP Basic, Digest or another
+/// authentication scheme, or by a proxy server.
+#[derive(Debug, Clone)]
+pub struct AuthRequest {
+    /// The origin of the page making the request.
+    pub origin_url: String,
+    /// The host name of the server, or of the proxy if |is_proxy| is true.
+    pub host: String,
+    pub port: u16,
+    /// The realm of the challenge, may be empty.
+    pub realm: String,
+    /// The authentication scheme, e.g. "basic" or "digest".
+    pub scheme: String,
+    /// Whether the credentials are requested by a proxy server.
+    pub is_proxy: bool,
+}
+
+/// Callback interface used for asynchronous continuation of authentication
+/// requests.
+///
+/// The callback can be answered from any thread. Dropping the callback
+/// without answering cancels the authentication request.
+pub struct AuthCallback(*mut c_void);
+
+unsafe impl Send for AuthCallback {}
+unsafe impl Sync for AuthCallback {}
+
+impl AuthCallback {
+    pub(crate) fn new(raw: *mut c_void) -> Self {
+        Self(raw)
+    }
+
+    /// Continue the authentication request.
+    pub fn proceed(mut self, username: &str, password: &str) {
+        let username = ffi::into(username);
+        let password = ffi::into(password);
+
+        unsafe { auth_callback_continue(self.0, username, password) }
+        self.0 = std::ptr::null_mut();
+
+        ffi::free(username);
+        ffi::free(password);
+    }
+
+    /// Cancel the authentication request.
+    pub fn cancel(self) {
+        drop(self)
+    }
+}
+
+impl Drop for AuthCallback {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { auth_callback_cancel(self.0) }
+        }
+    }
+}
+
+pub(crate) mod wrapper {
+    use std::ptr::null;
+
+    use webview_sys::ProxyMode;
+
+    use crate::ffi;
+
+    use super::Proxy;
+
+    pub(crate) struct ProxyOptions(pub webview_sys::ProxyOptions);
+
+    impl ProxyOptions {
+        pub(crate) fn new(proxy: &Proxy) -> Self {
+            let mut options = webview_sys::ProxyOptions {
+                mode: ProxyMode::kProxyDirect,
+                server: null(),
+                bypass_list: null(),
+                pac_url: null(),
+            };
+
+            match proxy {
+                Proxy::Direct => (),
+                Proxy::FixedServers {
+                    server,
+                    bypass_list,
+                } => {
+                    options.mode = ProxyMode::kProxyFixedServers;
+                    options.server = ffi::into(server);
+                    options.bypass_list = ffi::into_opt(*bypass_list);
+                }
+                Proxy::PacScript(url) => {
+                    options.mode = ProxyMode::kProxyPacScript;
+                    options.pac_url = ffi::into(url);
+                }
+                Proxy::System => {
+                    options.mode = ProxyMode::kProxySystem;
+                }
+            }
+
+            Self(options)
+        }
+
+        pub(crate) fn as_ptr(options: Option<&Self>) -> *const webview_sys::ProxyOptions {
+            options.map(|it| &it.0 as *const _).unwrap_or_else(null)
+        }
+    }
+
+    impl Drop for ProxyOptions {
+        fn drop(&mut self) {
+            ffi::free(self.0.server);
+            ffi::free(self.0.bypass_list);
+            ffi::free(self.0.pac_url);
+        }
+    }
+}
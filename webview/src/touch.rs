@@ -0,0 +1,144 @@
+use webview_sys::{TouchEventType, TouchPointerType};
+
+use crate::Modifiers;
+
+/// A touch, pen or eraser event.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TouchEvent {
+    /// Id of a touch point. Must be unique per touch, can be any number except
+    /// -1. Note that a maximum of 16 concurrent touches will be tracked;
+    /// touches beyond that will be ignored.
+    pub id: i32,
+    /// The position in view coordinates.
+    pub x: f32,
+    pub y: f32,
+    /// The radii of the contact ellipse in view coordinates, 0 if not
+    /// applicable.
+    pub radius_x: f32,
+    pub radius_y: f32,
+    /// The rotation of the contact ellipse in radians, 0 if not applicable.
+    pub rotation_angle: f32,
+    /// The normalized pressure in the range of [0, 1], 0 if not applicable.
+    pub pressure: f32,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::recorder::remote::TouchEventType")
+    )]
+    pub ty: TouchEventType,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::recorder::remote::TouchPointerType")
+    )]
+    pub pointer_type: TouchPointerType,
+    pub modifiers: Modifiers,
+}
+
+impl TouchEvent {
+    /// A touch of a finger without contact details.
+    pub fn new(id: i32, x: f32, y: f32, ty: TouchEventType) -> Self {
+        Self {
+            id,
+            x,
+            y,
+            radius_x: 0.0,
+            radius_y: 0.0,
+            rotation_angle: 0.0,
+            pressure: 0.0,
+            ty,
+            pointer_type: TouchPointerType::kTouch,
+            modifiers: Modifiers::empty(),
+        }
+    }
+}
+
+/// A two finger gesture made of touch events.
+///
+/// The gestures use the touch ids in `TouchGesture::TOUCH_IDS`, so they must
+/// not be mixed with other touches using these ids.
+#[derive(Debug, Clone, Copy)]
+pub enum TouchGesture {
+    /// Two fingers around |x| and |y| move apart or together until their
+    /// distance is |scale| times the start distance, a scale above 1 zooms
+    /// in.
+    Pinch { x: f32, y: f32, scale: f32 },
+    /// Two fingers around |x| and |y| move by |delta_x| and |delta_y|. The
+    /// content follows the fingers, so positive values scroll the page left
+    /// and up like `WheelDelta`.
+    TwoFingerScroll {
+        x: f32,
+        y: f32,
+        delta_x: f32,
+        delta_y: f32,
+    },
+}
+
+impl TouchGesture {
+    /// The touch ids of the two fingers.
+    pub const TOUCH_IDS: [i32; 2] = [0, 1];
+
+    /// The distance of the fingers in view coordinates at the start of a
+    /// gesture.
+    pub const FINGER_DISTANCE: f32 = 100.0;
+
+    /// The events of the gesture with |steps| moves, grouped by the time they
+    /// happen: both fingers press, move |steps| times and release.
+    pub fn to_events(&self, steps: u32) -> Vec<[TouchEvent; 2]> {
+        let steps = steps.max(1);
+        let mut events = Vec::with_capacity(steps as usize + 2);
+
+        events.push(self.fingers(0.0, TouchEventType::kTouchPressed));
+        for step in 1..=steps {
+            events.push(self.fingers(step as f32 / steps as f32, TouchEventType::kTouchMoved));
+        }
+
+        events.push(self.fingers(1.0, TouchEventType::kTouchReleased));
+        events
+    }
+
+    /// The two fingers at |progress| from 0 to 1 through the gesture.
+    fn fingers(&self, progress: f32, ty: TouchEventType) -> [TouchEvent; 2] {
+        let half = Self::FINGER_DISTANCE / 2.0;
+        let [(x1, y1), (x2, y2)] = match *self {
+            Self::Pinch { x, y, scale } => {
+                let half = half * (1.0 + (scale - 1.0) * progress);
+                [(x - half, y), (x + half, y)]
+            }
+            Self::TwoFingerScroll {
+                x,
+                y,
+                delta_x,
+                delta_y,
+            } => {
+                let (x, y) = (x + delta_x * progress, y + delta_y * progress);
+                [(x - half, y), (x + half, y)]
+            }
+        };
+
+        [
+            TouchEvent::new(Self::TOUCH_IDS[0], x1, y1, ty),
+            TouchEvent::new(Self::TOUCH_IDS[1], x2, y2, ty),
+        ]
+    }
+}
+
+pub(crate) mod wrapper {
+    use super::TouchEvent;
+
+    impl TouchEvent {
+        pub(crate) fn to_raw(self) -> webview_sys::TouchEvent {
+            webview_sys::TouchEvent {
+                id: self.id,
+                x: self.x,
+                y: self.y,
+                radius_x: self.radius_x,
+                radius_y: self.radius_y,
+                rotation_angle: self.rotation_angle,
+                pressure: self.pressure.clamp(0.0, 1.0),
+                type_: self.ty,
+                modifiers: self.modifiers.bits(),
+                pointer_type: self.pointer_type,
+            }
+        }
+    }
+}
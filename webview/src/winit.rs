@@ -0,0 +1,400 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use webview_sys::{MouseButtons, Rect, TouchEventType};
+use winit::{
+    dpi::{LogicalPosition, LogicalSize, PhysicalPosition},
+    event::{ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent},
+    keyboard::{self, Key, ModifiersState, PhysicalKey},
+    window::{CursorIcon, Window},
+};
+
+use crate::{
+    ActionState, CursorType, ImeAction, ImeUnderline, KeyCode, KeyEvent, KeyLocation, Modifiers,
+    MouseAction, Page, Position, TouchEvent, WheelDelta,
+};
+
+/// The longest time between the presses of a multi click.
+const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The longest distance in view coordinates between the presses of a multi
+/// click.
+const MULTI_CLICK_DISTANCE: i32 = 4;
+
+/// The last press of a mouse button, to count multi clicks.
+struct Click {
+    button: MouseButton,
+    position: Position,
+    time: Instant,
+    count: u32,
+}
+
+/// Feeds the events of a winit window to a page.
+///
+/// The adapter owns the input state winit only reports as changes, the cursor
+/// position, the modifiers, the click count and the IME composition. The page
+/// fills the view of the window, the output of the page is applied back to the
+/// window with `set_cursor` and `set_ime_cursor_area` from the `Observer`.
+pub struct WinitAdapter {
+    page: Arc<Page>,
+    scale_factor: f64,
+    position: Position,
+    modifiers: Modifiers,
+    click: Option<Click>,
+    is_composing: bool,
+}
+
+impl WinitAdapter {
+    /// Create an adapter for a page showing in |window|, this allows IME input
+    /// for the window.
+    pub fn new(page: Arc<Page>, window: &Window) -> Self {
+        window.set_ime_allowed(true);
+
+        Self {
+            page,
+            scale_factor: window.scale_factor(),
+            position: Position { x: 0, y: 0 },
+            modifiers: Modifiers::empty(),
+            click: None,
+            is_composing: false,
+        }
+    }
+
+    pub fn page(&self) -> &Arc<Page> {
+        &self.page
+    }
+
+    /// Feed an event of the window to the page, returns false if the event
+    /// is not an input of the page.
+    pub fn on_window_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::Resized(size) => {
+                let size = size.to_logical::<u32>(self.scale_factor);
+                self.page.resize(size.width, size.height);
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale_factor = *scale_factor;
+                self.page.notify_screen_info_changed(*scale_factor as f32);
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                for (key, flag) in [
+                    (ModifiersState::SHIFT, Modifiers::SHIFT),
+                    (ModifiersState::CONTROL, Modifiers::CTRL),
+                    (ModifiersState::ALT, Modifiers::ALT),
+                    (ModifiersState::SUPER, Modifiers::META),
+                ] {
+                    self.modifiers.set(flag, state.contains(key));
+                }
+            }
+            WindowEvent::Focused(focused) => {
+                self.page.set_focus(*focused);
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.position = self.to_position(*position);
+                self.page
+                    .on_mouse(MouseAction::Move(self.position), self.modifiers);
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.page.on_mouse(MouseAction::Leave, self.modifiers);
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let mouse_button = match button {
+                    MouseButton::Left => MouseButtons::kLeft,
+                    MouseButton::Right => MouseButtons::kRight,
+                    MouseButton::Middle => MouseButtons::kMiddle,
+                    MouseButton::Back => MouseButtons::kBack,
+                    MouseButton::Forward => MouseButtons::kForward,
+                    MouseButton::Other(_) => return false,
+                };
+
+                let state = to_state(*state);
+                let click_count = self.click_count(*button, state);
+                self.page.on_mouse(
+                    MouseAction::Click {
+                        button: mouse_button,
+                        state,
+                        position: Some(self.position),
+                        click_count,
+                    },
+                    self.modifiers,
+                );
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => WheelDelta::Line(*x, *y),
+                    MouseScrollDelta::PixelDelta(delta) => {
+                        let delta = delta.to_logical::<f32>(self.scale_factor);
+                        WheelDelta::Pixel(delta.x, delta.y)
+                    }
+                };
+
+                self.page.on_mouse(
+                    MouseAction::Wheel {
+                        position: self.position,
+                        delta,
+                    },
+                    self.modifiers,
+                );
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                let PhysicalKey::Code(code) = event.physical_key else {
+                    return false;
+                };
+
+                let Some(code) = to_key_code(code) else {
+                    return false;
+                };
+
+                let mut key_event = KeyEvent::new(code, to_state(event.state));
+                key_event.repeat = event.repeat;
+                key_event.modifiers = self.modifiers;
+                key_event.location = match event.location {
+                    keyboard::KeyLocation::Standard => KeyLocation::Standard,
+                    keyboard::KeyLocation::Left => KeyLocation::Left,
+                    keyboard::KeyLocation::Right => KeyLocation::Right,
+                    keyboard::KeyLocation::Numpad => KeyLocation::Numpad,
+                };
+
+                // The logical key of letters and digits follows the layout.
+                if let Key::Character(it) = &event.logical_key {
+                    let mut chars = it.chars();
+                    if let (Some(it), None) = (chars.next(), chars.next()) {
+                        if it.is_ascii_alphanumeric() {
+                            key_event.windows_key_code = Some(it.to_ascii_uppercase() as u32);
+                        }
+                    }
+                }
+
+                // Keys pressed with ctrl may produce control characters, leave
+                // them to the shortcut handling of the page instead. Enter is
+                // the only control character inserting text.
+                if event.state == ElementState::Pressed {
+                    key_event.text = Some(
+                        event
+                            .text
+                            .as_deref()
+                            .filter(|it| *it == "\r" || !it.chars().all(char::is_control))
+                            .unwrap_or_default()
+                            .to_string(),
+                    );
+                }
+
+                self.page.on_keyboard(&key_event);
+            }
+            WindowEvent::Ime(ime) => match ime {
+                Ime::Enabled => (),
+                Ime::Preedit(text, cursor) => {
+                    if text.is_empty() {
+                        if self.is_composing {
+                            self.is_composing = false;
+                            self.page.on_ime(ImeAction::Cancel);
+                        }
+                    } else {
+                        self.is_composing = true;
+
+                        let underline = ImeUnderline::new(0..utf16_len(text));
+                        let selection_range = cursor
+                            .map(|(start, end)| utf16_len(&text[..start])..utf16_len(&text[..end]));
+
+                        self.page.on_ime(ImeAction::SetComposition {
+                            text,
+                            underlines: &[underline],
+                            replacement_range: None,
+                            selection_range,
+                        });
+                    }
+                }
+                Ime::Commit(text) => {
+                    self.is_composing = false;
+                    self.page.on_ime(ImeAction::Commit {
+                        text,
+                        replacement_range: None,
+                        relative_cursor_position: 0,
+                    });
+                }
+                Ime::Disabled => {
+                    if self.is_composing {
+                        self.is_composing = false;
+                        self.page.on_ime(ImeAction::Cancel);
+                    }
+                }
+            },
+            WindowEvent::Touch(touch) => {
+                let position = touch.location.to_logical::<f32>(self.scale_factor);
+                let mut event = TouchEvent::new(
+                    // The id must not be -1.
+                    (touch.id & 0x7FFFFFFF) as i32,
+                    position.x,
+                    position.y,
+                    match touch.phase {
+                        TouchPhase::Started => TouchEventType::kTouchPressed,
+                        TouchPhase::Moved => TouchEventType::kTouchMoved,
+                        TouchPhase::Ended => TouchEventType::kTouchReleased,
+                        TouchPhase::Cancelled => TouchEventType::kTouchCancelled,
+                    },
+                );
+
+                event.pressure = touch.force.map(|it| it.normalized() as f32).unwrap_or(0.0);
+                event.modifiers = self.modifiers;
+                self.page.on_touch(&event);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn to_position(&self, position: PhysicalPosition<f64>) -> Position {
+        let position = position.to_logical::<f64>(self.scale_factor);
+        Position {
+            x: position.x.round() as i32,
+            y: position.y.round() as i32,
+        }
+    }
+
+    /// Count the presses of |button| at the current position, a release keeps
+    /// the count of its press.
+    fn click_count(&mut self, button: MouseButton, state: ActionState) -> u32 {
+        let now = Instant::now();
+        let position = self.position;
+        let is_same = |it: &Click| {
+            it.button == button
+                && (it.position.x - position.x).abs() <= MULTI_CLICK_DISTANCE
+                && (it.position.y - position.y).abs() <= MULTI_CLICK_DISTANCE
+        };
+
+        match (&mut self.click, state) {
+            (Some(click), ActionState::Up) if click.button == button => click.count,
+            (_, ActionState::Up) => 1,
+            (Some(click), ActionState::Down)
+                if is_same(click) && now - click.time <= MULTI_CLICK_INTERVAL =>
+            {
+                click.count += 1;
+                click.time = now;
+                click.count
+            }
+            (click, ActionState::Down) => {
+                *click = Some(Click {
+                    button,
+                    position,
+                    time: now,
+                    count: 1,
+                });
+
+                1
+            }
+        }
+    }
+}
+
+/// Show the cursor of `Observer::on_cursor_change` on the window.
+///
+/// Custom cursors need the event loop to be created, they are shown as the
+/// default cursor.
+pub fn set_cursor(window: &Window, cursor: &CursorType) {
+    let icon = match cursor {
+        CursorType::None => {
+            window.set_cursor_visible(false);
+            return;
+        }
+        CursorType::Default | CursorType::Custom(_) => CursorIcon::Default,
+        CursorType::Crosshair => CursorIcon::Crosshair,
+        CursorType::Pointer => CursorIcon::Pointer,
+        CursorType::Text => CursorIcon::Text,
+        CursorType::VerticalText => CursorIcon::VerticalText,
+        CursorType::Wait => CursorIcon::Wait,
+        CursorType::Progress => CursorIcon::Progress,
+        CursorType::Help => CursorIcon::Help,
+        CursorType::Move => CursorIcon::Move,
+        CursorType::AllScroll => CursorIcon::AllScroll,
+        CursorType::Cell => CursorIcon::Cell,
+        CursorType::ContextMenu => CursorIcon::ContextMenu,
+        CursorType::Alias => CursorIcon::Alias,
+        CursorType::Copy => CursorIcon::Copy,
+        CursorType::NoDrop => CursorIcon::NoDrop,
+        CursorType::NotAllowed => CursorIcon::NotAllowed,
+        CursorType::ZoomIn => CursorIcon::ZoomIn,
+        CursorType::ZoomOut => CursorIcon::ZoomOut,
+        CursorType::Grab => CursorIcon::Grab,
+        CursorType::Grabbing => CursorIcon::Grabbing,
+        CursorType::EResize => CursorIcon::EResize,
+        CursorType::NResize => CursorIcon::NResize,
+        CursorType::NeResize => CursorIcon::NeResize,
+        CursorType::NwResize => CursorIcon::NwResize,
+        CursorType::SResize => CursorIcon::SResize,
+        CursorType::SeResize => CursorIcon::SeResize,
+        CursorType::SwResize => CursorIcon::SwResize,
+        CursorType::WResize => CursorIcon::WResize,
+        CursorType::EwResize => CursorIcon::EwResize,
+        CursorType::NsResize => CursorIcon::NsResize,
+        CursorType::NeswResize => CursorIcon::NeswResize,
+        CursorType::NwseResize => CursorIcon::NwseResize,
+        CursorType::ColResize => CursorIcon::ColResize,
+        CursorType::RowResize => CursorIcon::RowResize,
+    };
+
+    window.set_cursor_visible(true);
+    window.set_cursor(icon);
+}
+
+/// Place the candidate window of the input method under the composition, with
+/// the character bounds of `Observer::on_ime_composition_range_changed`.
+pub fn set_ime_cursor_area(window: &Window, character_bounds: &[Rect]) {
+    let Some(first) = character_bounds.first() else {
+        return;
+    };
+
+    let (mut left, mut top) = (first.x, first.y);
+    let (mut right, mut bottom) = (first.x + first.width, first.y + first.height);
+    for it in character_bounds {
+        left = left.min(it.x);
+        top = top.min(it.y);
+        right = right.max(it.x + it.width);
+        bottom = bottom.max(it.y + it.height);
+    }
+
+    window.set_ime_cursor_area(
+        LogicalPosition::new(left, top),
+        LogicalSize::new(right - left, bottom - top),
+    );
+}
+
+fn to_state(state: ElementState) -> ActionState {
+    match state {
+        ElementState::Pressed => ActionState::Down,
+        ElementState::Released => ActionState::Up,
+    }
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+macro_rules! key_codes {
+    ($($code:ident),* $(,)?) => {
+        fn to_key_code(code: keyboard::KeyCode) -> Option<KeyCode> {
+            Some(match code {
+                keyboard::KeyCode::SuperLeft => KeyCode::MetaLeft,
+                keyboard::KeyCode::SuperRight => KeyCode::MetaRight,
+                $(keyboard::KeyCode::$code => KeyCode::$code,)*
+                _ => return None,
+            })
+        }
+    };
+}
+
+key_codes! {
+    Escape, Backquote, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    Digit0, Minus, Equal, Backspace, Tab, KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI,
+    KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX,
+    KeyY, KeyZ, BracketLeft, BracketRight, Backslash, IntlBackslash, CapsLock, Semicolon, Quote,
+    Enter, Comma, Period, Slash, Space, ShiftLeft, ShiftRight, ControlLeft, ControlRight, AltLeft,
+    AltRight, ContextMenu, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16,
+    F17, F18, F19, F20, F21, F22, F23, F24, PrintScreen, ScrollLock, Pause, Insert, Delete, Home,
+    End, PageUp, PageDown, ArrowUp, ArrowDown, ArrowLeft, ArrowRight, NumLock, Numpad0, Numpad1,
+    Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9, NumpadAdd,
+    NumpadSubtract, NumpadMultiply, NumpadDivide, NumpadDecimal, NumpadEqual, NumpadEnter,
+}